@@ -0,0 +1,39 @@
+//! Criterion benchmarks of every solver, built from the same registry as the `bench` subcommand, so
+//! `cargo bench -- day09` and Criterion's baselines and plots work too.
+
+use aoc_2025::{CountingAlloc, get_puzzle_input, list_solvers, prepare_solver};
+use criterion::{Criterion, criterion_group, criterion_main};
+use itertools::Itertools;
+use std::hint::black_box;
+
+// the same allocator as the bench subcommand, so the solvers run with the same overhead
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc::new();
+
+fn solvers(c: &mut Criterion) {
+    for (day, parts) in &list_solvers().into_iter().chunk_by(|&(day, _)| day) {
+        let input = match get_puzzle_input(day) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("skipping day {day}: {e:#}");
+                continue;
+            }
+        };
+
+        let mut group = c.benchmark_group(format!("day{day:02}"));
+        for (_, part) in parts {
+            let solver = prepare_solver(day, part, &input).expect("listed solvers exist");
+            // parts that aren't solved would only measure how fast they give up
+            if solver.run().is_none() {
+                continue;
+            }
+            group.bench_function(format!("part{part}"), |b| {
+                b.iter(|| black_box(&solver).run())
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, solvers);
+criterion_main!(benches);
@@ -2,6 +2,7 @@ use quote::quote;
 use regex::Regex;
 use std::error::Error;
 use std::path::Path;
+use std::process::Command;
 
 fn get_solved_days(solutions_path: &Path) -> Result<Vec<usize>, Box<dyn Error>> {
     let day_regex = Regex::new(r"^day(?<day>\d{2})\.rs$").unwrap();
@@ -30,6 +31,21 @@ fn get_solved_days(solutions_path: &Path) -> Result<Vec<usize>, Box<dyn Error>>
         .map_err(From::from)
 }
 
+/// The days with a `#[generator(day = N)]`, found by looking for the attribute in their source
+fn get_generator_days(solutions_path: &Path, days: &[usize]) -> Result<Vec<usize>, Box<dyn Error>> {
+    let generator_regex = Regex::new(r"#\[generator\(day\s*=\s*(?<day>\d+)\s*\)\]").unwrap();
+    let mut generator_days = Vec::new();
+    for day in days {
+        let source = std::fs::read_to_string(solutions_path.join(format!("day{day:02}.rs")))?;
+        generator_days.extend(
+            generator_regex
+                .captures_iter(&source)
+                .map(|captures| captures["day"].parse::<usize>().unwrap()),
+        );
+    }
+    Ok(generator_days)
+}
+
 fn main() {
     println!("cargo::rerun-if-changed=src/solutions");
     println!("cargo::rerun-if-changed=build.rs");
@@ -40,15 +56,34 @@ fn main() {
     let out_dir = std::env::var("OUT_DIR").unwrap();
     let dest_path = Path::new(&out_dir).join("solved_days.rs");
 
-    let generated_code = generate_solver_map(solved_days);
+    let generator_days = get_generator_days(solutions_path, &solved_days).unwrap();
+    let generated_code = generate_solver_map(solved_days, generator_days);
 
     std::fs::write(&dest_path, generated_code).expect("Failed to write solved_days.rs");
+
+    // cached benchmarks are only valid for the toolchain and profile they were measured with
+    let rustc = std::env::var("RUSTC").unwrap_or("rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+    println!("cargo::rustc-env=AOC_RUSTC_VERSION={rustc_version}");
+    println!(
+        "cargo::rustc-env=AOC_BUILD_PROFILE={}",
+        std::env::var("PROFILE").unwrap()
+    );
 }
 
-fn generate_solver_map(days: Vec<usize>) -> String {
+fn generate_solver_map(days: Vec<usize>, generator_days: Vec<usize>) -> String {
+    let source_paths = days
+        .iter()
+        .map(|day| format!("/src/solutions/day{day:02}.rs"))
+        .collect::<Vec<_>>();
+
     let hashmap_code = quote! {
-        use std::collections::HashMap;
-        use crate::aoc::{PuzzleInput, Solver, SolverMap};
+        use std::collections::{HashMap, HashSet};
+        use crate::aoc::{Day, Generator, GeneratorMap, Part, PuzzleInput, Solver, SolverMap};
 
         pub fn get_solvers() -> SolverMap {
             let mut map: SolverMap = HashMap::new();
@@ -66,6 +101,34 @@ fn generate_solver_map(days: Vec<usize>) -> String {
             );)*
             map
         }
+
+        pub fn get_unsolved_parts() -> HashSet<(Day, Part)> {
+            let mut unsolved = HashSet::new();
+            #(if <PuzzleInput as Solver<#days, 1>>::UNSOLVED {
+                unsolved.insert((#days, 1));
+            })*
+            #(if <PuzzleInput as Solver<#days, 2>>::UNSOLVED {
+                unsolved.insert((#days, 2));
+            })*
+            unsolved
+        }
+
+        /// The days with a `#[generator]`
+        pub fn get_generators() -> GeneratorMap {
+            let mut map: GeneratorMap = HashMap::new();
+            #(map.insert(
+                #generator_days,
+                Box::new(<PuzzleInput as Generator<#generator_days>>::generate)
+            );)*
+            map
+        }
+
+        /// The source file of each day
+        pub fn get_day_sources() -> HashMap<Day, &'static str> {
+            HashMap::from([
+                #((#days, include_str!(concat!(env!("CARGO_MANIFEST_DIR"), #source_paths))),)*
+            ])
+        }
     };
 
     // Format the generated code with prettyplease.
@@ -2,7 +2,33 @@ use darling::FromMeta;
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::spanned::Spanned;
-use syn::{FnArg, ReturnType};
+use syn::visit_mut::VisitMut;
+use syn::{FnArg, Lifetime, ReturnType, Type, TypeReference};
+
+/// Rewrites every lifetime in a type (named, `'_` or elided references) to `'__input`, the lifetime
+/// of the borrowed `PuzzleInput`. `'static` is left alone.
+struct InputLifetime;
+
+impl VisitMut for InputLifetime {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident != "static" {
+            *lifetime = Lifetime::new("'__input", lifetime.span());
+        }
+    }
+
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        if reference.lifetime.is_none() {
+            reference.lifetime = Some(Lifetime::new("'__input", reference.and_token.span()));
+        }
+        syn::visit_mut::visit_type_reference_mut(self, reference);
+    }
+}
+
+fn with_input_lifetime(ty: &Type) -> Type {
+    let mut ty = ty.clone();
+    InputLifetime.visit_type_mut(&mut ty);
+    ty
+}
 
 #[proc_macro_attribute]
 pub fn parser(_args: TokenStream, input: TokenStream) -> TokenStream {
@@ -18,10 +44,11 @@ pub fn parser(_args: TokenStream, input: TokenStream) -> TokenStream {
     };
 
     let name = &input.sig.ident;
+    let return_type = with_input_lifetime(return_type);
 
     quote! {
-        impl From<&PuzzleInput> for #return_type {
-            fn from(input: &PuzzleInput) -> Self {
+        impl<'__input> From<&'__input PuzzleInput> for #return_type {
+            fn from(input: &'__input PuzzleInput) -> Self {
                 #name(input).into()
             }
         }
@@ -70,7 +97,7 @@ pub fn solution(args: TokenStream, input: TokenStream) -> TokenStream {
         .into();
     };
 
-    let arg_type = &function_param.ty;
+    let arg_type = with_input_lifetime(&function_param.ty);
 
     let day = args.day;
     let part = args.part;
@@ -78,26 +105,77 @@ pub fn solution(args: TokenStream, input: TokenStream) -> TokenStream {
     let solution_impl = if args.unsolved {
         quote! {
             impl Solver<#day, #part> for PuzzleInput {
-                type Input = #arg_type;
-                fn solve(&self, input: Self::Input) -> Option<&'static str> {
-                    None
+                type Input<'__input> = #arg_type;
+                const UNSOLVED: bool = true;
+                // the same signature as a solved part, since the input lifetime makes the return
+                // type capture it
+                fn solve(&self, _input: Self::Input<'_>) -> Option<impl std::fmt::Display + std::fmt::Debug> {
+                    None::<&'static str>
                 }
             }
         }
     } else {
         quote! {
             impl Solver<#day, #part> for PuzzleInput {
-                type Input = #arg_type;
-                fn solve(&self, input: Self::Input) -> Option<impl std::fmt::Display + std::fmt::Debug> {
+                type Input<'__input> = #arg_type;
+                fn solve(&self, input: Self::Input<'_>) -> Option<impl std::fmt::Display + std::fmt::Debug> {
                     Some(#name(input))
                 }
             }
         }
     };
 
+    // an unsolved part keeps its function for when it's done, but nothing calls it yet
+    let allow_unused = args.unsolved.then(|| quote! { #[allow(dead_code)] });
+
     quote! {
         #solution_impl
 
+        #allow_unused
+        #input
+    }
+    .into()
+}
+
+#[derive(FromMeta)]
+#[darling(derive_syn_parse)]
+struct GeneratorArgs {
+    day: usize,
+}
+
+/// Marks a `fn(size: usize, rng: &mut fastrand::Rng) -> String` that generates a synthetic input for
+/// a day. The build script picks up every day that has one.
+#[proc_macro_attribute]
+pub fn generator(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args: GeneratorArgs = match syn::parse(args) {
+        Ok(args) => args,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let input: syn::ItemFn = match syn::parse(input) {
+        Ok(input) => input,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    if input.sig.inputs.len() != 2 {
+        return syn::Error::new(
+            input.sig.span(),
+            "expected a size and a random number generator",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let name = &input.sig.ident;
+    let day = args.day;
+
+    quote! {
+        impl Generator<#day> for PuzzleInput {
+            fn generate(size: usize, rng: &mut fastrand::Rng) -> PuzzleInput {
+                #name(size, rng).into()
+            }
+        }
+
         #input
     }
     .into()
@@ -0,0 +1,137 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Wraps the system allocator and keeps track of the heap usage and the number of allocations.
+/// While heap profiling is on, allocations go through dhat instead, which records where each one
+/// was made.
+///
+/// Linking the library doesn't install it, the binary and the benches do:
+///
+/// ```
+/// #[global_allocator]
+/// static ALLOCATOR: aoc_2025::CountingAlloc = aoc_2025::CountingAlloc::new();
+/// ```
+///
+/// Without it, the memory usage and allocation counts of benchmarks are all 0.
+pub struct CountingAlloc;
+
+/// The counters are shared by every `CountingAlloc`, so the benchmark can read them without
+/// knowing which static was installed
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static DEALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+static HEAP_PROFILING: AtomicBool = AtomicBool::new(false);
+
+/// A snapshot of the allocator's counters. Everything except `current` and `peak` only ever
+/// grows, so the difference between two snapshots is what happened in between.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct AllocStats {
+    pub current: usize,
+    pub peak: usize,
+    pub allocations: usize,
+    pub deallocations: usize,
+    pub allocated_bytes: usize,
+}
+
+impl CountingAlloc {
+    pub const fn new() -> Self {
+        CountingAlloc
+    }
+
+    /// Blocks allocated by one of these can be freed by the other: dhat allocates from the system
+    /// allocator and ignores blocks it didn't see being allocated
+    fn inner(&self) -> &'static dyn GlobalAlloc {
+        if HEAP_PROFILING.load(Ordering::Relaxed) {
+            &dhat::Alloc
+        } else {
+            &System
+        }
+    }
+
+    fn record_alloc(&self, size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(&self, size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Send allocations through dhat, which only records them while a `dhat::Profiler` is running.
+/// It's much slower, so it's off by default.
+pub fn set_heap_profiling(enabled: bool) {
+    HEAP_PROFILING.store(enabled, Ordering::SeqCst);
+}
+
+pub fn stats() -> AllocStats {
+    AllocStats {
+        current: CURRENT.load(Ordering::Relaxed),
+        peak: PEAK.load(Ordering::Relaxed),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+        allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+    }
+}
+
+/// Start tracking the peak again from the current usage
+pub fn reset_peak() {
+    PEAK.store(CURRENT.load(Ordering::Relaxed), Ordering::Relaxed);
+}
+
+impl Default for CountingAlloc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AllocStats {
+    /// What was allocated since `start`, with the peak relative to the usage at `start`
+    pub fn since(&self, start: &AllocStats) -> AllocStats {
+        AllocStats {
+            current: self.current.saturating_sub(start.current),
+            peak: self.peak.saturating_sub(start.current),
+            allocations: self.allocations - start.allocations,
+            deallocations: self.deallocations - start.deallocations,
+            allocated_bytes: self.allocated_bytes - start.allocated_bytes,
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.inner().alloc(layout) };
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { self.inner().dealloc(ptr, layout) };
+        self.record_dealloc(layout.size());
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.inner().alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    // a reallocation counts as freeing the old block and allocating the new one
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { self.inner().realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            self.record_dealloc(layout.size());
+            self.record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
@@ -0,0 +1,232 @@
+use crate::aoc::{Day, Part};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::env::current_dir;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::PathBuf;
+
+/// Known-correct answers, stored in `answers.toml`.
+///
+/// Each answer is keyed by day, part and the hash of the input it was accepted for, so answers for
+/// several accounts can live in the same file. Answers without an input hash apply to any input.
+/// Answers saved by `verify --save` are marked `unverified` until the flag is removed by hand, after
+/// the site accepted them.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AnswersFile {
+    #[serde(default, rename = "answer")]
+    pub answers: Vec<KnownAnswer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnownAnswer {
+    pub day: Day,
+    pub part: Part,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    pub answer: String,
+    /// Recorded from a solver but never confirmed, so it's neither a star nor checked against
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unverified: bool,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Verification {
+    Correct,
+    Mismatch {
+        expected: String,
+        actual: Option<String>,
+    },
+    /// There's no known answer for this input
+    Unknown {
+        actual: Option<String>,
+    },
+    /// The only answer for this input is unverified, so it can't tell whether this one is right
+    Unverified {
+        saved: String,
+        actual: Option<String>,
+    },
+}
+
+pub fn get_answers_path() -> PathBuf {
+    current_dir().unwrap().join("answers.toml")
+}
+
+impl AnswersFile {
+    /// Load the answers file, if there is one
+    pub fn load() -> anyhow::Result<Option<Self>> {
+        let path = get_answers_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        Ok(Some(toml::from_str(&contents)?))
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        fs::write(get_answers_path(), toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: Day, part: Part, input_hash: &str) -> Option<&KnownAnswer> {
+        let matching = self
+            .answers
+            .iter()
+            .filter(|answer| answer.day == day && answer.part == part);
+
+        // an answer for this exact input takes priority over one that applies to any input
+        matching
+            .clone()
+            .find(|answer| answer.input.as_deref() == Some(input_hash))
+            .or_else(|| matching.clone().find(|answer| answer.input.is_none()))
+    }
+
+    pub fn insert(&mut self, answer: KnownAnswer) {
+        self.answers.retain(|known| {
+            (known.day, known.part, &known.input) != (answer.day, answer.part, &answer.input)
+        });
+        self.answers.push(answer);
+        self.answers = self
+            .answers
+            .drain(..)
+            .sorted_by(|a, b| (a.day, a.part, &a.input).cmp(&(b.day, b.part, &b.input)))
+            .collect();
+    }
+
+    pub fn verify(
+        &self,
+        day: Day,
+        part: Part,
+        input_hash: &str,
+        actual: Option<String>,
+    ) -> Verification {
+        match self.get(day, part, input_hash) {
+            Some(known) if known.unverified => Verification::Unverified {
+                saved: known.answer.clone(),
+                actual,
+            },
+            Some(known) if actual.as_ref() == Some(&known.answer) => Verification::Correct,
+            Some(known) => Verification::Mismatch {
+                expected: known.answer.clone(),
+                actual,
+            },
+            None => Verification::Unknown { actual },
+        }
+    }
+}
+
+impl Verification {
+    pub fn status(&self) -> &'static str {
+        match self {
+            Verification::Correct => "correct",
+            Verification::Mismatch { .. } => "mismatch",
+            Verification::Unknown { actual: Some(_) } => "unknown",
+            Verification::Unknown { actual: None } => "not_solved",
+            Verification::Unverified { .. } => "unverified",
+        }
+    }
+}
+
+impl Display for Verification {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verification::Correct => write!(f, "ok"),
+            Verification::Mismatch { expected, actual } => write!(
+                f,
+                "MISMATCH\n  - expected: {expected}\n  + actual:   {}",
+                actual.as_deref().unwrap_or("(no answer)")
+            ),
+            Verification::Unknown { actual: Some(_) } => write!(f, "no known answer"),
+            Verification::Unknown { actual: None } => write!(f, "not solved"),
+            Verification::Unverified { saved, actual } if actual.as_ref() == Some(saved) => {
+                write!(f, "unverified, same as the saved answer")
+            }
+            Verification::Unverified { saved, actual } => write!(
+                f,
+                "unverified, changed\n  - saved:  {saved}\n  + actual: {}",
+                actual.as_deref().unwrap_or("(no answer)")
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(day: Day, part: Part, input: Option<&str>, answer: &str) -> KnownAnswer {
+        KnownAnswer {
+            day,
+            part,
+            input: input.map(str::to_string),
+            answer: answer.to_string(),
+            unverified: false,
+        }
+    }
+
+    #[test]
+    fn test_verify_by_input_hash() {
+        let mut answers = AnswersFile::default();
+        answers.insert(answer(1, 1, Some("aaaa"), "10"));
+        answers.insert(answer(1, 1, Some("bbbb"), "20"));
+        answers.insert(answer(1, 2, None, "30"));
+
+        assert_eq!(
+            answers.verify(1, 1, "aaaa", Some("10".to_string())),
+            Verification::Correct
+        );
+        assert_eq!(
+            answers.verify(1, 1, "bbbb", Some("10".to_string())),
+            Verification::Mismatch {
+                expected: "20".to_string(),
+                actual: Some("10".to_string())
+            }
+        );
+        assert_eq!(
+            answers.verify(1, 1, "cccc", Some("10".to_string())),
+            Verification::Unknown {
+                actual: Some("10".to_string())
+            }
+        );
+        assert_eq!(
+            answers.verify(1, 2, "cccc", Some("30".to_string())),
+            Verification::Correct
+        );
+    }
+
+    #[test]
+    fn test_unverified_answers_are_not_checked() {
+        let mut answers = AnswersFile::default();
+        answers.insert(KnownAnswer {
+            unverified: true,
+            ..answer(1, 1, Some("aaaa"), "10")
+        });
+
+        assert_eq!(
+            answers.verify(1, 1, "aaaa", Some("11".to_string())),
+            Verification::Unverified {
+                saved: "10".to_string(),
+                actual: Some("11".to_string())
+            }
+        );
+
+        let saved = toml::to_string(&answers).unwrap();
+        assert!(saved.contains("unverified = true"));
+        let loaded: AnswersFile = toml::from_str(&saved).unwrap();
+        assert!(loaded.answers[0].unverified);
+    }
+
+    #[test]
+    fn test_insert_replaces_existing_answer() {
+        let mut answers = AnswersFile::default();
+        answers.insert(answer(2, 1, Some("aaaa"), "1"));
+        answers.insert(answer(1, 1, Some("aaaa"), "2"));
+        answers.insert(answer(2, 1, Some("aaaa"), "3"));
+
+        assert_eq!(answers.answers.len(), 2);
+        assert_eq!(answers.answers[0].day, 1);
+        assert_eq!(answers.get(2, 1, "aaaa").unwrap().answer, "3");
+    }
+}
@@ -1,9 +1,14 @@
+use crate::utils::hex_encode;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
 
 pub type Day = usize;
 pub type Part = usize;
-pub type SolverMap = HashMap<(Day, Part), Box<dyn Fn(&PuzzleInput) -> Option<String>>>;
+pub type SolverFn = Box<dyn Fn(&PuzzleInput) -> Option<String> + Send + Sync>;
+pub type SolverMap = HashMap<(Day, Part), SolverFn>;
+pub type GeneratorMap =
+    HashMap<Day, Box<dyn Fn(usize, &mut fastrand::Rng) -> PuzzleInput + Send + Sync>>;
 
 pub const CURRENT_YEAR: u32 = 2025;
 
@@ -11,7 +16,7 @@ pub const CURRENT_YEAR: u32 = 2025;
 /// Declare a test to run a part
 ///
 /// Usage:
-/// ```rust
+/// ```rust,ignore
 /// fn part_1(input: impl Lines) -> u64 {
 ///     // implementation
 ///     3159281
@@ -48,6 +53,11 @@ impl PuzzleInput {
     pub fn get_lines(&self) -> impl Iterator<Item = &str> {
         self.get_raw().lines().map(str::trim)
     }
+
+    /// A short hash identifying this input, since every account gets different inputs
+    pub fn hash(&self) -> String {
+        hex_encode(&Sha256::digest(self.input.trim_end().as_bytes())[..10])
+    }
 }
 
 impl<'a> From<&'a str> for PuzzleInput {
@@ -58,6 +68,12 @@ impl<'a> From<&'a str> for PuzzleInput {
     }
 }
 
+impl From<String> for PuzzleInput {
+    fn from(input: String) -> Self {
+        Self { input }
+    }
+}
+
 impl From<&Vec<String>> for PuzzleInput {
     fn from(value: &Vec<String>) -> Self {
         Self {
@@ -67,18 +83,29 @@ impl From<&Vec<String>> for PuzzleInput {
 }
 
 pub trait Solver<const D: usize, const P: usize> {
-    type Input: for<'a> From<&'a PuzzleInput>;
-    fn solve(&self, input: Self::Input) -> Option<impl Display + Debug>;
+    /// The parsed input, which may borrow from the raw [`PuzzleInput`]
+    type Input<'a>: From<&'a PuzzleInput>;
+    /// Set by `#[solution(unsolved)]` for parts that don't have a real solution yet
+    const UNSOLVED: bool = false;
+    fn solve(&self, input: Self::Input<'_>) -> Option<impl Display + Debug>;
+}
+
+/// Implemented by `#[generator(day = N)]` to produce a synthetic input of roughly `size` elements,
+/// for measuring how a day's solvers scale
+pub trait Generator<const D: usize> {
+    fn generate(size: usize, rng: &mut fastrand::Rng) -> PuzzleInput;
 }
 
 pub trait PuzzleSource {
     fn get_input(&self, day: Day) -> anyhow::Result<PuzzleInput>;
 }
 
+#[cfg(test)]
 pub struct FixedDataSource {
     pub lines: Vec<String>,
 }
 
+#[cfg(test)]
 impl PuzzleSource for FixedDataSource {
     fn get_input(&self, _day: Day) -> anyhow::Result<PuzzleInput> {
         Ok(PuzzleInput::from(&self.lines))
@@ -92,7 +119,8 @@ pub fn get_days_iter() -> impl Iterator<Item = Day> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use derive_solution::{parser, solution};
+    use derive_solution::{generator, parser, solution};
+    use itertools::Itertools;
 
     pub struct Input(Vec<u64>);
 
@@ -111,6 +139,22 @@ mod tests {
         )
     }
 
+    pub struct Lines<'a> {
+        lines: Vec<&'a str>,
+    }
+
+    #[solution(day = 100, part = 2)]
+    fn longest_line<'a>(input: Lines<'a>) -> &'a str {
+        input.lines.into_iter().max_by_key(|l| l.len()).unwrap()
+    }
+
+    #[parser]
+    fn parse_lines(input: &PuzzleInput) -> Lines<'_> {
+        Lines {
+            lines: input.get_lines().collect(),
+        }
+    }
+
     impl From<PuzzleInput> for Vec<u64> {
         fn from(input: PuzzleInput) -> Self {
             input
@@ -120,8 +164,25 @@ mod tests {
         }
     }
 
+    #[generator(day = 100)]
+    fn generate_lines(size: usize, rng: &mut fastrand::Rng) -> String {
+        (0..size).map(|_| rng.u64(0..100).to_string()).join("\n")
+    }
+
+    #[test]
+    fn test_generator() {
+        let input = <PuzzleInput as Generator<100>>::generate(50, &mut fastrand::Rng::with_seed(1));
+        assert_eq!(input.get_lines().count(), 50);
+        assert!(<PuzzleInput as Solver<100, 1>>::solve(&input, (&input).into()).is_some());
+    }
+
     #[test]
     fn test_implemented_solver() {
         aoc_test!(100, 1, 6, "1\n2\n3");
     }
+
+    #[test]
+    fn test_borrowed_input_solver() {
+        aoc_test!(100, 2, "ccc", "a\nccc\nbb");
+    }
 }
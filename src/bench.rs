@@ -1,17 +1,225 @@
-use crate::PEAK_ALLOC;
+use crate::alloc::{self, AllocStats};
+use crate::cpu_time::CpuTime;
+use crate::fingerprint::Fingerprint;
+use crate::progress;
+use crate::stack::{BENCHMARK_STACK_SIZE, measure_stack};
 use serde::{Deserialize, Serialize};
-use std::cmp::min;
 use std::fmt::{Display, Formatter};
+use std::panic::resume_unwind;
+use std::thread;
 use std::time::{Duration, Instant};
 
-const TARGET_DURATION_PER_PART: Duration = Duration::from_secs(5);
+/// Solvers faster than this are timed in batches, so the timer overhead doesn't dominate a sample
+const MIN_SAMPLE_DURATION: Duration = Duration::from_micros(10);
 const MAX_RUNS: usize = 1_000_000_000;
 
+/// How much time to spend benchmarking each part, and how many iterations to run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchmarkPolicy {
+    /// The number of iterations is picked to roughly fill this much time
+    pub target_time: Duration,
+    pub min_iterations: usize,
+    pub max_iterations: usize,
+    /// Untimed runs before measuring. Warming up stops early after a tenth of the target time.
+    pub warmup_iterations: usize,
+}
+
+impl Default for BenchmarkPolicy {
+    fn default() -> Self {
+        Self {
+            target_time: Duration::from_secs(5),
+            min_iterations: 1,
+            max_iterations: MAX_RUNS,
+            warmup_iterations: 1000,
+        }
+    }
+}
+
+impl BenchmarkPolicy {
+    /// A short run for checking that nothing is badly off
+    pub fn quick() -> Self {
+        Self {
+            target_time: Duration::from_millis(500),
+            warmup_iterations: 10,
+            ..Self::default()
+        }
+    }
+
+    /// A long run for publishing results
+    pub fn precise() -> Self {
+        Self {
+            target_time: Duration::from_secs(30),
+            min_iterations: 10,
+            warmup_iterations: 10_000,
+            ..Self::default()
+        }
+    }
+}
+
+impl Display for BenchmarkPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} per part, {}-{} iterations, {} warm-up runs",
+            format_duration(self.target_time),
+            self.min_iterations,
+            self.max_iterations,
+            self.warmup_iterations
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct BenchmarkResults {
+pub struct BenchmarkResults {
     pub iterations: usize,
     pub average_duration: Duration,
     pub peak_memory: usize,
+    /// Number of heap allocations in a single run, including reallocations
+    #[serde(default)]
+    pub allocations: usize,
+    #[serde(default)]
+    pub deallocations: usize,
+    /// Total bytes allocated in a single run, even if they were freed again
+    #[serde(default)]
+    pub allocated_bytes: usize,
+    /// Deepest stack usage of a single run, in bytes
+    #[serde(default)]
+    pub peak_stack: usize,
+    #[serde(default)]
+    pub min_duration: Duration,
+    #[serde(default)]
+    pub median_duration: Duration,
+    #[serde(default)]
+    pub p95_duration: Duration,
+    #[serde(default)]
+    pub std_dev: Duration,
+    /// Average CPU time of the whole process per run, including any threads the solver used
+    #[serde(default)]
+    pub cpu_user: Duration,
+    #[serde(default)]
+    pub cpu_system: Duration,
+    /// Average CPU time of the thread that ran the solver, user and system combined
+    #[serde(default)]
+    pub thread_cpu: Duration,
+    /// Samples outside of 1.5 times the interquartile range
+    #[serde(default)]
+    pub outliers: usize,
+    #[serde(default)]
+    pub sample_count: usize,
+    /// The time per iteration of every sample; batched samples are divided by the batch size
+    #[serde(skip)]
+    pub samples: Vec<Duration>,
+    /// Hash of the input the solver ran on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_hash: Option<String>,
+    /// The answer the solver returned, which isn't cached
+    #[serde(skip)]
+    pub answer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+    /// The source, input or toolchain changed since this was measured
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stale: bool,
+}
+
+impl BenchmarkResults {
+    fn from_samples(
+        iterations: usize,
+        samples: Vec<Duration>,
+        memory: AllocStats,
+        peak_stack: usize,
+    ) -> Self {
+        let sorted = {
+            let mut sorted = samples.clone();
+            sorted.sort();
+            sorted
+        };
+        let percentile = |p: f64| sorted[((sorted.len() - 1) as f64 * p).round() as usize];
+
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / sorted.len() as f64;
+        let variance = sorted
+            .iter()
+            .map(|d| (d.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / sorted.len() as f64;
+
+        let (q1, q3) = (percentile(0.25), percentile(0.75));
+        let fence = (q3 - q1).mul_f64(1.5);
+        let outliers = sorted
+            .iter()
+            .filter(|&&d| d + fence < q1 || d > q3 + fence)
+            .count();
+
+        BenchmarkResults {
+            iterations,
+            average_duration: Duration::from_secs_f64(mean),
+            peak_memory: memory.peak,
+            allocations: memory.allocations,
+            deallocations: memory.deallocations,
+            allocated_bytes: memory.allocated_bytes,
+            peak_stack,
+            min_duration: sorted[0],
+            median_duration: percentile(0.5),
+            p95_duration: percentile(0.95),
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+            cpu_user: Duration::ZERO,
+            cpu_system: Duration::ZERO,
+            thread_cpu: Duration::ZERO,
+            outliers,
+            sample_count: samples.len(),
+            samples,
+            input_hash: None,
+            answer: None,
+            fingerprint: None,
+            stale: false,
+        }
+    }
+
+    fn with_cpu_time(self, process: CpuTime, thread: CpuTime) -> Self {
+        let process = process.per_run(self.iterations);
+        BenchmarkResults {
+            cpu_user: process.user,
+            cpu_system: process.system,
+            thread_cpu: thread.per_run(self.iterations).total(),
+            ..self
+        }
+    }
+
+    /// User and system time of the whole process per run
+    pub fn cpu_time(&self) -> Duration {
+        self.cpu_user + self.cpu_system
+    }
+}
+
+#[cfg(test)]
+impl BenchmarkResults {
+    /// A single iteration that took `duration`, with nothing else measured, for tests that need
+    /// results without running a benchmark
+    pub(crate) fn fixture(duration: Duration) -> Self {
+        BenchmarkResults {
+            iterations: 1,
+            average_duration: duration,
+            peak_memory: 0,
+            allocations: 0,
+            deallocations: 0,
+            allocated_bytes: 0,
+            peak_stack: 0,
+            min_duration: duration,
+            median_duration: duration,
+            p95_duration: duration,
+            std_dev: Duration::ZERO,
+            cpu_user: Duration::ZERO,
+            cpu_system: Duration::ZERO,
+            thread_cpu: Duration::ZERO,
+            outliers: 0,
+            sample_count: 1,
+            samples: vec![duration],
+            input_hash: None,
+            answer: None,
+            fingerprint: None,
+            stale: false,
+        }
+    }
 }
 
 pub fn format_duration(duration: Duration) -> String {
@@ -39,6 +247,16 @@ pub fn format_memory(bytes: usize) -> String {
     }
 }
 
+pub fn format_count(count: usize) -> String {
+    if count < 1_000 {
+        count.to_string()
+    } else if count < 1_000_000 {
+        format!("{:.1}k", count as f64 / 1_000.0)
+    } else {
+        format!("{:.1}M", count as f64 / 1_000_000.0)
+    }
+}
+
 impl Display for BenchmarkResults {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let iter = match self.iterations {
@@ -48,62 +266,145 @@ impl Display for BenchmarkResults {
 
         write!(
             f,
-            "{} / {} peak ({iter})",
+            "{} / {} CPU / {} peak / {} stack (median {}, p95 {}, ±{}, {} outliers, {iter}, {} allocs / {} frees, {} allocated)",
             format_duration(self.average_duration),
+            format_duration(self.cpu_time()),
             format_memory(self.peak_memory),
+            format_memory(self.peak_stack),
+            format_duration(self.median_duration),
+            format_duration(self.p95_duration),
+            format_duration(self.std_dev),
+            self.outliers,
+            format_count(self.allocations),
+            format_count(self.deallocations),
+            format_memory(self.allocated_bytes),
         )
     }
 }
 
-pub(crate) fn benchmark<T, F: Fn() -> Option<T>>(
+#[cfg(test)]
+pub fn benchmark<T, F: Fn() -> Option<T> + Sync>(
+    bench_fn: F,
+) -> Result<BenchmarkResults, BenchmarkError> {
+    benchmark_with_policy(bench_fn, &BenchmarkPolicy::default())
+}
+
+/// Runs on a dedicated thread with a known stack size, so the stack usage can be measured
+pub fn benchmark_with_policy<T, F: Fn() -> Option<T> + Sync>(
+    bench_fn: F,
+    policy: &BenchmarkPolicy,
+) -> Result<BenchmarkResults, BenchmarkError> {
+    thread::scope(|scope| {
+        thread::Builder::new()
+            .name("benchmark".to_string())
+            .stack_size(BENCHMARK_STACK_SIZE)
+            .spawn_scoped(scope, || run_benchmark(&bench_fn, policy))
+            .expect("failed to start the benchmark thread")
+            .join()
+            .unwrap_or_else(|e| resume_unwind(e))
+    })
+}
+
+fn run_benchmark<T, F: Fn() -> Option<T>>(
     bench_fn: F,
+    policy: &BenchmarkPolicy,
 ) -> Result<BenchmarkResults, BenchmarkError> {
     // run the function to get an idea of how long it takes
+    let (process_start, thread_start) = (CpuTime::process(), CpuTime::thread());
     let start = Instant::now();
     let _ = bench_fn().ok_or(BenchmarkError::NotImplemented)?;
     let first_run_duration = start.elapsed();
+    let first_run_cpu = (
+        CpuTime::process().since(&process_start),
+        CpuTime::thread().since(&thread_start),
+    );
 
     // measure the memory usage
     // it's important that this is done in a second run because the stdlib might allocate
     // things when first called, which would mess up the memory usage for part 1
-    PEAK_ALLOC.reset_peak_usage();
-    let initial_mem = PEAK_ALLOC.current_usage();
-
-    bench_fn();
+    let (used_mem, peak_stack) = {
+        let _paused = progress::pause_display();
+        alloc::reset_peak();
+        let initial_mem = alloc::stats();
+        let (_, peak_stack) = measure_stack(&bench_fn);
+        (alloc::stats().since(&initial_mem), peak_stack)
+    };
 
-    let peak_mem = PEAK_ALLOC.peak_usage();
-    let used_mem = peak_mem - initial_mem;
+    if first_run_duration > policy.target_time && policy.min_iterations <= 1 {
+        return Ok(BenchmarkResults::from_samples(
+            1,
+            vec![first_run_duration],
+            used_mem,
+            peak_stack,
+        )
+        .with_cpu_time(first_run_cpu.0, first_run_cpu.1));
+    }
 
-    if first_run_duration > TARGET_DURATION_PER_PART {
-        Ok(BenchmarkResults {
-            iterations: 1,
-            average_duration: first_run_duration,
-            peak_memory: used_mem,
-        })
+    // warm up caches and the CPU frequency, and get a better estimate of the time per iteration
+    let mut warmup_runs = 0u32;
+    let start = Instant::now();
+    while (warmup_runs as usize) < policy.warmup_iterations
+        && start.elapsed() < policy.target_time / 10
+    {
+        if progress::interrupted() {
+            return Err(BenchmarkError::Interrupted);
+        }
+        _ = bench_fn().unwrap();
+        warmup_runs += 1;
+    }
+    let estimated_duration = if warmup_runs > 0 {
+        start.elapsed() / warmup_runs
     } else {
-        let project_runs = (TARGET_DURATION_PER_PART.as_secs_f64()
-            / first_run_duration.as_secs_f64())
-        .ceil() as usize;
-        let iterations = min(MAX_RUNS, project_runs);
+        first_run_duration
+    }
+    .max(Duration::from_nanos(1));
+
+    let project_runs =
+        (policy.target_time.as_secs_f64() / estimated_duration.as_secs_f64()).ceil() as usize;
+    let runs = project_runs
+        .max(policy.min_iterations)
+        .min(policy.max_iterations)
+        .max(1);
+    let batch_size = ((MIN_SAMPLE_DURATION.as_secs_f64() / estimated_duration.as_secs_f64()).ceil()
+        as usize)
+        .clamp(1, runs);
+    let total_samples = runs.div_ceil(batch_size);
 
+    progress::start_timing(runs);
+    let (process_start, thread_start) = (CpuTime::process(), CpuTime::thread());
+    let mut samples = Vec::with_capacity(total_samples);
+    for sample in 0..total_samples {
+        // the last batch only runs what's left, so there are never more than `runs` iterations
+        let batch_size = batch_size.min(runs - sample * batch_size);
+        // a partly measured part would be cached as if it had the whole time budget
+        if progress::interrupted() {
+            return Err(BenchmarkError::Interrupted);
+        }
         let start = Instant::now();
-        for _ in 0..iterations {
+        for _ in 0..batch_size {
             _ = bench_fn().unwrap();
         }
-        let duration = start.elapsed();
-
-        Ok(BenchmarkResults {
-            iterations,
-            average_duration: duration / (iterations as u32),
-            peak_memory: used_mem,
-        })
+        samples.push(start.elapsed() / batch_size as u32);
+        progress::add_iterations(batch_size);
     }
+
+    let (process_cpu, thread_cpu) = (
+        CpuTime::process().since(&process_start),
+        CpuTime::thread().since(&thread_start),
+    );
+
+    Ok(
+        BenchmarkResults::from_samples(runs, samples, used_mem, peak_stack)
+            .with_cpu_time(process_cpu, thread_cpu),
+    )
 }
 
 #[derive(Debug, thiserror::Error, Eq, PartialEq)]
-pub(crate) enum BenchmarkError {
+pub enum BenchmarkError {
     #[error("not implemented")]
     NotImplemented,
+    #[error("interrupted")]
+    Interrupted,
 }
 
 #[cfg(test)]
@@ -141,11 +442,84 @@ mod tests {
 
     #[test]
     fn test_benchmark_slow_solver() {
-        let bench = benchmark(very_slow_solver);
+        // the solver has to be slower than the whole budget to only run once; with the default 5s
+        // it gets a few 2s runs, which is why this test used to fail
+        let policy = BenchmarkPolicy {
+            target_time: Duration::from_secs(1),
+            ..BenchmarkPolicy::default()
+        };
+        let bench = benchmark_with_policy(very_slow_solver, &policy);
         assert!(bench.is_ok());
         assert_eq!(1, bench.unwrap().iterations);
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_benchmark_cpu_time() {
+        let policy = BenchmarkPolicy {
+            target_time: Duration::from_millis(100),
+            min_iterations: 5,
+            max_iterations: 5,
+            warmup_iterations: 0,
+        };
+
+        // a sleeping solver takes time without using the CPU
+        let sleeping = benchmark_with_policy(
+            || {
+                sleep(Duration::from_millis(10));
+                Some(())
+            },
+            &policy,
+        )
+        .unwrap();
+        assert!(sleeping.cpu_time() < sleeping.average_duration / 2);
+        assert!(sleeping.thread_cpu < sleeping.average_duration / 2);
+
+        let busy = benchmark_with_policy(
+            || {
+                let start = Instant::now();
+                while start.elapsed() < Duration::from_millis(10) {}
+                Some(())
+            },
+            &policy,
+        )
+        .unwrap();
+        assert!(busy.cpu_time() > busy.average_duration / 2);
+        assert!(busy.thread_cpu > busy.average_duration / 2);
+    }
+
+    #[test]
+    fn test_benchmark_policy_iteration_limits() {
+        let policy = BenchmarkPolicy {
+            target_time: Duration::from_millis(1),
+            min_iterations: 3,
+            max_iterations: 5,
+            warmup_iterations: 0,
+        };
+        let bench = benchmark_with_policy(very_fast_solver, &policy).unwrap();
+        assert_eq!(3, bench.iterations);
+
+        let policy = BenchmarkPolicy {
+            max_iterations: 5,
+            ..BenchmarkPolicy::quick()
+        };
+        let bench = benchmark_with_policy(|| Some(1 + 1), &policy).unwrap();
+        assert_eq!(5, bench.iterations);
+    }
+
+    #[test]
+    fn test_benchmark_batches_stay_within_max_iterations() {
+        // a tiny solver is timed in batches, which mustn't round the count up past the limit
+        let policy = BenchmarkPolicy {
+            max_iterations: 1003,
+            ..BenchmarkPolicy::quick()
+        };
+        let bench = benchmark_with_policy(|| Some(1 + 1), &policy).unwrap();
+        assert!(bench.iterations <= policy.max_iterations);
+        assert_eq!(bench.iterations, 1003);
+        assert!(bench.samples.len() <= bench.iterations);
+    }
+
     #[test]
     fn test_benchmark_fast_solver() {
         let bench = benchmark(very_fast_solver);
@@ -159,6 +533,38 @@ mod tests {
         assert_eq!(Some(BenchmarkError::NotImplemented), bench.err());
     }
 
+    #[test]
+    fn test_benchmark_batches_tiny_solver() {
+        let bench = benchmark(|| Some(1 + 1)).unwrap();
+        assert!(bench.iterations > bench.samples.len());
+        assert!(bench.min_duration <= bench.median_duration);
+        assert!(bench.median_duration <= bench.p95_duration);
+    }
+
+    #[test]
+    fn test_statistics_from_samples() {
+        let samples = [10, 11, 12, 12, 13, 14, 96]
+            .map(Duration::from_millis)
+            .to_vec();
+        let results = BenchmarkResults::from_samples(7, samples, AllocStats::default(), 0);
+
+        assert_eq!(results.min_duration, Duration::from_millis(10));
+        assert_eq!(results.median_duration, Duration::from_millis(12));
+        assert_eq!(results.p95_duration, Duration::from_millis(96));
+        assert_eq!(results.average_duration.as_micros(), 24_000);
+        assert_eq!(results.outliers, 1);
+        assert_eq!(results.std_dev.as_millis(), 29);
+    }
+
+    #[test]
+    fn test_benchmark_counts_allocations() {
+        // other tests allocate concurrently, so the counts can only be checked from below
+        let bench = benchmark(alloc_vec_solver).unwrap();
+        assert!(bench.allocations >= 1);
+        assert!(bench.deallocations >= 1);
+        assert!(bench.allocated_bytes >= 4 * 8);
+    }
+
     #[ignore]
     #[test]
     fn test_benchmark_alloc_vec_solver() {
@@ -167,11 +573,17 @@ mod tests {
         assert!(bench.unwrap().peak_memory >= 4 * 4); // 4 * i64 numbers
     }
 
-    #[ignore]
     #[test]
-    fn test_benchmark_stack_memory_solver() {
-        let bench = benchmark(|| Some(factorial_stack(1000.0)));
-        assert!(bench.is_ok());
-        assert_eq!(bench.unwrap().peak_memory, 0);
+    fn test_benchmark_measures_stack() {
+        let policy = BenchmarkPolicy {
+            max_iterations: 1,
+            ..BenchmarkPolicy::quick()
+        };
+        let shallow = benchmark_with_policy(|| Some(factorial_stack(1.0)), &policy).unwrap();
+        let deep = benchmark_with_policy(|| Some(factorial_stack(1000.0)), &policy).unwrap();
+
+        assert!(deep.peak_stack > shallow.peak_stack);
+        // every level of the recursion needs at least a return address and the argument
+        assert!(deep.peak_stack - shallow.peak_stack >= 1000 * 16);
     }
 }
@@ -1,6 +1,6 @@
 use crate::BenchmarkMap;
 use crate::aoc::{Day, Part};
-use crate::bench::BenchmarkResults;
+use crate::bench::{BenchmarkPolicy, BenchmarkResults};
 use crate::utils::get_cpu_name;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
@@ -12,26 +12,97 @@ pub fn get_cached_benchmarks_path() -> PathBuf {
     current_dir().unwrap().join(".benchmark_cache.toml")
 }
 
-pub fn get_cached_benchmarks() -> Option<BenchmarkMap> {
-    let cache_file = get_cached_benchmarks_path();
-    let cached = fs::read_to_string(&cache_file).ok()?;
-    let cached_value: CachedBenchmarks = toml::from_str(&cached).ok()?;
-
-    if cached_value.cpu_name == get_cpu_name() {
-        Some(
-            cached_value
-                .benchmarks
-                .into_iter()
-                .map(|item| ((item.day, item.part), item.result))
-                .collect(),
-        )
-    } else {
+/// The cached results of one machine
+#[derive(Debug, Clone)]
+pub struct MachineBenchmarks {
+    pub cpu_name: String,
+    pub benchmarks: BenchmarkMap,
+}
+
+/// The cache holds a `[[machine]]` table for every CPU that was benchmarked
+#[derive(Default, Serialize, Deserialize)]
+struct CacheFile {
+    #[serde(default, rename = "machine")]
+    machines: Vec<CachedBenchmarks>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedBenchmarks {
+    cpu_name: String,
+    /// Caches from before the policy was configurable used the default one
+    #[serde(default)]
+    policy: BenchmarkPolicy,
+    benchmarks: Vec<CachedItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedItem {
+    pub day: Day,
+    pub part: Part,
+    pub result: BenchmarkResults,
+}
+
+impl CachedBenchmarks {
+    fn to_map(&self) -> BenchmarkMap {
+        self.benchmarks
+            .iter()
+            .map(|item| ((item.day, item.part), item.result.clone()))
+            .collect()
+    }
+}
+
+/// Older caches only held the results of a single machine
+fn parse_cache_file(contents: &str) -> Option<CacheFile> {
+    match toml::from_str::<CacheFile>(contents) {
+        Ok(cache) if !cache.machines.is_empty() => Some(cache),
+        _ => toml::from_str::<CachedBenchmarks>(contents)
+            .ok()
+            .map(|machine| CacheFile {
+                machines: vec![machine],
+            }),
+    }
+}
+
+fn read_cache_file() -> Option<CacheFile> {
+    parse_cache_file(&fs::read_to_string(get_cached_benchmarks_path()).ok()?)
+}
+
+/// The cached results of this CPU, if they were measured with the same policy
+pub fn get_cached_benchmarks(policy: &BenchmarkPolicy) -> Option<BenchmarkMap> {
+    let cpu_name = get_cpu_name();
+    let cache = read_cache_file()?;
+    let cached_value = cache.machines.iter().find(|m| m.cpu_name == cpu_name)?;
+
+    if cached_value.policy != *policy {
+        log::warn!(
+            "ignoring cached benchmarks measured with a different policy ({})",
+            cached_value.policy
+        );
         None
+    } else {
+        Some(cached_value.to_map())
     }
 }
 
-pub fn save_cached_benchmarks(cached_benchmarks: &BenchmarkMap) {
+/// The cached results of every machine, starting with this one
+pub fn get_all_cached_benchmarks() -> Vec<MachineBenchmarks> {
+    let cpu_name = get_cpu_name();
+    read_cache_file()
+        .map(|cache| cache.machines)
+        .unwrap_or_default()
+        .iter()
+        .sorted_by_key(|m| (m.cpu_name != cpu_name, m.cpu_name.clone()))
+        .map(|m| MachineBenchmarks {
+            cpu_name: m.cpu_name.clone(),
+            benchmarks: m.to_map(),
+        })
+        .collect()
+}
+
+/// Replace the cached results of this CPU, keeping the ones of other machines
+pub fn save_cached_benchmarks(cached_benchmarks: &BenchmarkMap, policy: &BenchmarkPolicy) {
     let cache_file = get_cached_benchmarks_path();
+    let cpu_name = get_cpu_name();
     let benchmarks = cached_benchmarks
         .iter()
         .sorted_by_key(|(k, _)| **k)
@@ -42,22 +113,44 @@ pub fn save_cached_benchmarks(cached_benchmarks: &BenchmarkMap) {
         })
         .collect();
 
-    let cached_value = CachedBenchmarks {
-        cpu_name: get_cpu_name(),
+    let mut cache = read_cache_file().unwrap_or_default();
+    cache.machines.retain(|m| m.cpu_name != cpu_name);
+    cache.machines.push(CachedBenchmarks {
+        cpu_name,
+        policy: policy.clone(),
         benchmarks,
-    };
-    fs::write(&cache_file, toml::to_string_pretty(&cached_value).unwrap()).unwrap();
-}
+    });
+    cache.machines.sort_by(|a, b| a.cpu_name.cmp(&b.cpu_name));
 
-#[derive(Serialize, Deserialize)]
-struct CachedBenchmarks {
-    cpu_name: String,
-    benchmarks: Vec<CachedItem>,
+    fs::write(&cache_file, toml::to_string_pretty(&cache).unwrap()).unwrap();
 }
 
-#[derive(Serialize, Deserialize)]
-struct CachedItem {
-    day: Day,
-    part: Part,
-    result: BenchmarkResults,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_single_machine_cache() {
+        let legacy = r#"
+            cpu_name = "1x Old CPU"
+
+            [[benchmarks]]
+            day = 1
+            part = 1
+
+            [benchmarks.result]
+            iterations = 10
+            average_duration = { secs = 0, nanos = 500 }
+            peak_memory = 64
+        "#;
+
+        let cache = parse_cache_file(legacy).unwrap();
+        assert_eq!(cache.machines.len(), 1);
+        assert_eq!(cache.machines[0].cpu_name, "1x Old CPU");
+        assert_eq!(cache.machines[0].policy, BenchmarkPolicy::default());
+        assert_eq!(cache.machines[0].to_map()[&(1, 1)].iterations, 10);
+
+        let serialized = toml::to_string_pretty(&cache).unwrap();
+        assert_eq!(parse_cache_file(&serialized).unwrap().machines.len(), 1);
+    }
 }
@@ -0,0 +1,109 @@
+use crate::BenchmarkMap;
+use crate::aoc::{Day, Part};
+use crate::bench::{BenchmarkPolicy, BenchmarkResults};
+use crate::benchmark_cache::CachedItem;
+use crate::utils::get_cpu_name;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::env::current_dir;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn get_benchmark_history_path() -> PathBuf {
+    current_dir().unwrap().join(".benchmark_history.toml")
+}
+
+/// One benchmark run. The history file is a list of these, and is only ever appended to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub commit: Option<String>,
+    /// There were uncommitted changes when the benchmark ran
+    pub dirty: bool,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub cpu_name: String,
+    /// Not recorded by older runs
+    #[serde(default)]
+    pub policy: Option<BenchmarkPolicy>,
+    pub benchmarks: Vec<CachedItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HistoryFile {
+    #[serde(default)]
+    run: Vec<HistoryEntry>,
+}
+
+impl HistoryEntry {
+    pub fn get(&self, day: Day, part: Part) -> Option<&BenchmarkResults> {
+        self.benchmarks
+            .iter()
+            .find(|item| item.day == day && item.part == part)
+            .map(|item| &item.result)
+    }
+}
+
+fn get_git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn is_git_dirty() -> bool {
+    Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .output()
+        .is_ok_and(|output| !output.stdout.is_empty())
+}
+
+/// Append the results of a run to the history file
+pub fn append_benchmark_history(results: &BenchmarkMap, policy: &BenchmarkPolicy) {
+    if results.is_empty() {
+        return;
+    }
+
+    let entry = HistoryEntry {
+        commit: get_git_commit(),
+        dirty: is_git_dirty(),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+        cpu_name: get_cpu_name(),
+        policy: Some(policy.clone()),
+        benchmarks: results
+            .iter()
+            .sorted_by_key(|(k, _)| **k)
+            .map(|(&(day, part), result)| CachedItem {
+                day,
+                part,
+                result: result.clone(),
+            })
+            .collect(),
+    };
+
+    // appending another `[[run]]` table to the file keeps it a valid TOML array
+    let serialized = toml::to_string(&HistoryFile { run: vec![entry] }).unwrap();
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_benchmark_history_path())
+        .unwrap();
+    writeln!(file, "{serialized}").unwrap();
+}
+
+pub fn get_benchmark_history() -> Vec<HistoryEntry> {
+    fs::read_to_string(get_benchmark_history_path())
+        .ok()
+        .and_then(|contents| toml::from_str::<HistoryFile>(&contents).ok())
+        .map(|history| history.run)
+        .unwrap_or_default()
+}
@@ -0,0 +1,180 @@
+use crate::BenchmarkMap;
+use crate::aoc::{Part, get_days_iter};
+use crate::bench::{BenchmarkResults, format_duration, format_memory};
+use crate::report::xml_escape;
+use std::fmt::Write;
+use std::time::Duration;
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 360.0;
+const MARGIN_LEFT: f64 = 80.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 50.0;
+const MARGIN_BOTTOM: f64 = 40.0;
+const PART_COLORS: [&str; 2] = ["#4e79a7", "#f28e2b"];
+
+/// Bar chart of the average time of each part, on a log scale
+pub fn time_chart(results: &BenchmarkMap, cpu_name: &str) -> String {
+    bar_chart(
+        &format!("Time per part ({cpu_name})"),
+        results,
+        10.0,
+        |r| r.average_duration.as_nanos() as f64,
+        |nanos| format_duration(Duration::from_nanos(nanos as u64)),
+    )
+}
+
+/// Bar chart of the peak memory of each part, on a log scale
+pub fn memory_chart(results: &BenchmarkMap, cpu_name: &str) -> String {
+    bar_chart(
+        &format!("Peak memory per part ({cpu_name})"),
+        results,
+        // powers of 4 line up with KiB and MiB
+        4.0,
+        |r| r.peak_memory as f64,
+        |bytes| format_memory(bytes as usize),
+    )
+}
+
+/// A bar per part, grouped by day, on a log scale with a grid line at every power of `base`. Values
+/// that are zero can't be shown on a log scale, so they're left out.
+fn bar_chart(
+    title: &str,
+    results: &BenchmarkMap,
+    base: f64,
+    value: impl Fn(&BenchmarkResults) -> f64,
+    format_value: impl Fn(f64) -> String,
+) -> String {
+    let values = results
+        .values()
+        .map(&value)
+        .filter(|v| *v > 0.0)
+        .collect::<Vec<_>>();
+    // the axis covers whole powers of the base, so every bar has some height
+    let min_exp = values
+        .iter()
+        .map(|v| v.log(base).floor())
+        .fold(f64::INFINITY, f64::min);
+    let max_exp = values
+        .iter()
+        .map(|v| v.log(base).ceil())
+        .fold(f64::NEG_INFINITY, f64::max);
+    let (min_exp, max_exp) = if values.is_empty() {
+        (0.0, 1.0)
+    } else {
+        (min_exp, max_exp.max(min_exp + 1.0))
+    };
+
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let bottom = MARGIN_TOP + plot_height;
+    let y = |v: f64| bottom - (v.log(base) - min_exp) / (max_exp - min_exp) * plot_height;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\" \
+         viewBox=\"0 0 {WIDTH} {HEIGHT}\" font-family=\"sans-serif\" font-size=\"12\">"
+    );
+    let _ = writeln!(
+        svg,
+        "  <rect width=\"{WIDTH}\" height=\"{HEIGHT}\" fill=\"white\"/>\n  \
+         <text x=\"{}\" y=\"24\" text-anchor=\"middle\" font-size=\"16\">{}</text>",
+        WIDTH / 2.0,
+        xml_escape(title)
+    );
+
+    for (part, color) in PART_COLORS.iter().enumerate() {
+        let x = WIDTH - MARGIN_RIGHT - 140.0 + part as f64 * 70.0;
+        let _ = writeln!(
+            svg,
+            "  <rect x=\"{x}\" y=\"32\" width=\"10\" height=\"10\" fill=\"{color}\"/>\
+             <text x=\"{}\" y=\"41\">Part {}</text>",
+            x + 14.0,
+            part + 1
+        );
+    }
+
+    for exp in (min_exp as i32)..=(max_exp as i32) {
+        let tick = base.powi(exp);
+        let _ = writeln!(
+            svg,
+            "  <line x1=\"{MARGIN_LEFT}\" x2=\"{}\" y1=\"{y:.1}\" y2=\"{y:.1}\" stroke=\"#ddd\"/>\
+             <text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            WIDTH - MARGIN_RIGHT,
+            MARGIN_LEFT - 6.0,
+            y(tick) + 4.0,
+            xml_escape(&format_value(tick)),
+            y = y(tick),
+        );
+    }
+
+    let days = get_days_iter().collect::<Vec<_>>();
+    let group_width = plot_width / days.len() as f64;
+    let bar_width = group_width * 0.35;
+    for (i, &day) in days.iter().enumerate() {
+        let group_x = MARGIN_LEFT + i as f64 * group_width;
+        let _ = writeln!(
+            svg,
+            "  <text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{day:02}</text>",
+            group_x + group_width / 2.0,
+            bottom + 18.0
+        );
+
+        for part in 1..=2 as Part {
+            let Some(v) = results.get(&(day, part)).map(&value).filter(|v| *v > 0.0) else {
+                continue;
+            };
+            let x = group_x + group_width * 0.15 + (part - 1) as f64 * bar_width;
+            let top = y(v);
+            let _ = writeln!(
+                svg,
+                "  <rect x=\"{x:.1}\" y=\"{top:.1}\" width=\"{bar_width:.1}\" height=\"{:.1}\" \
+                 fill=\"{}\"><title>Day {day} part {part}: {}</title></rect>",
+                bottom - top,
+                PART_COLORS[part - 1],
+                xml_escape(&format_value(v))
+            );
+        }
+    }
+
+    let _ = writeln!(
+        svg,
+        "  <line x1=\"{MARGIN_LEFT}\" x2=\"{MARGIN_LEFT}\" y1=\"{MARGIN_TOP}\" y2=\"{bottom}\" stroke=\"black\"/>\n  \
+         <line x1=\"{MARGIN_LEFT}\" x2=\"{}\" y1=\"{bottom}\" y2=\"{bottom}\" stroke=\"black\"/>\n</svg>",
+        WIDTH - MARGIN_RIGHT
+    );
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_chart_uses_log_scale() {
+        let result = |nanos| BenchmarkResults::fixture(Duration::from_nanos(nanos));
+        let results = BenchmarkMap::from([((1, 1), result(500)), ((9, 2), result(5_000_000))]);
+        let svg = time_chart(&results, "Test CPU");
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Time per part (Test CPU)"));
+        // a grid line for every power of ten from 100ns to 10ms
+        assert!(svg.contains(">100ns<") && svg.contains(">10.0ms<"));
+        assert!(!svg.contains(">1ns<") && !svg.contains(">100.0ms<"));
+        assert!(svg.contains("<title>Day 9 part 2: 5.0ms</title>"));
+        assert_eq!(svg.matches("<title>").count(), 2);
+    }
+
+    #[test]
+    fn test_memory_chart_uses_powers_of_4() {
+        let result = BenchmarkResults {
+            peak_memory: 3000,
+            ..BenchmarkResults::fixture(Duration::from_micros(1))
+        };
+        let svg = memory_chart(&BenchmarkMap::from([((1, 1), result)]), "Test CPU");
+
+        assert!(svg.contains(">1 KiB<") && svg.contains(">4 KiB<"));
+        assert!(!svg.contains(">256 bytes<") && !svg.contains(">16 KiB<"));
+    }
+}
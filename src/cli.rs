@@ -0,0 +1,861 @@
+use crate::answers::{AnswersFile, KnownAnswer, Verification};
+use crate::aoc::{Day, Part, PuzzleSource, SolverMap, get_days_iter};
+use crate::bench::{BenchmarkPolicy, format_duration, format_memory};
+use crate::benchmark_cache::{
+    get_all_cached_benchmarks, get_cached_benchmarks, save_cached_benchmarks,
+};
+use crate::benchmark_history::{append_benchmark_history, get_benchmark_history};
+use crate::compare::compare_benchmarks;
+use crate::config::{BenchConfig, Config};
+use crate::fingerprint::mark_stale;
+use crate::heap_profile::{HeapProfileOptions, format_allocation_sites, heap_profile_solver};
+use crate::html::html_report;
+use crate::inputs::CachedOnlinePuzzleSource;
+use crate::isolate::{
+    IsolationOptions, WORKER_COMMAND, run_isolated_benchmarks, run_worker_process,
+};
+use crate::profile::{ProfileOptions, profile_solver};
+use crate::progress::{self, ProgressDisplay, ProgressLogger};
+use crate::readme::{ReadmeOptions, format_table, update_readme};
+use crate::report::{OutputFormat, PartReport, Report};
+use crate::scaling::{DEFAULT_SIZES, format_scaling, run_scaling};
+use crate::solutions::{get_generators, get_solvers};
+use crate::stars::Stars;
+use crate::threads::{ThreadCount, format_thread_scaling, run_thread_scaling};
+use crate::utils::format_timestamp;
+use crate::visualize;
+use crate::visualize::image::{ImageFormat, ImageSequenceVisualizer};
+use crate::visualize::terminal::TerminalVisualizer;
+use crate::{BenchmarkMap, get_last_day, run_benchmarks};
+use clap::{Arg, ArgMatches, Command, arg, command, value_parser};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use std::{fs, iter};
+
+/// Everything the `aoc-2025` binary does
+pub(crate) fn main() -> Result<(), String> {
+    let logger = pretty_env_logger::formatted_builder()
+        .filter_level(log::LevelFilter::Info)
+        .build();
+    log::set_max_level(logger.filter());
+    log::set_boxed_logger(Box::new(ProgressLogger(logger))).map_err(|e| e.to_string())?;
+
+    let puzzle_source = CachedOnlinePuzzleSource::new().expect("failed to configure puzzle source");
+    let solvers = get_solvers();
+
+    let matches = command!()
+        .subcommand(
+            Command::new("bench")
+                .about("Run the benchmark")
+                .arg(arg!([day] "which day to run"))
+                .arg(format_arg())
+                .arg(arg!(--"readme-stats" "show the median, spread and p95 in the README"))
+                .arg(arg!(--"readme-allocs" "show the allocation counts in the README"))
+                .arg(arg!(--"readme-combined" "show every CPU in one README table"))
+                .arg(
+                    arg!(--html <path> "write a report of this run to a static HTML page")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(--"readme-file" <path> "write the results to this file instead of README.md")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(arg!(--compare "compare against the cached results instead of saving them"))
+                .arg(
+                    arg!(--changed "only benchmark days whose source, input or toolchain changed")
+                        .conflicts_with_all(["day", "compare"]),
+                )
+                .arg(
+                    arg!(--scaling "benchmark on generated inputs of growing sizes and fit the complexity")
+                        .conflicts_with_all(["compare", "changed", "isolate"]),
+                )
+                .arg(
+                    arg!(--sizes <sizes> "comma-separated input sizes for --scaling")
+                        .value_parser(value_parser!(usize))
+                        .value_delimiter(',')
+                        .requires("scaling"),
+                )
+                .arg(
+                    arg!(--threads <counts> "benchmark in rayon pools of each size, e.g. 1,2,4,max")
+                        .value_parser(value_parser!(ThreadCount))
+                        .value_delimiter(',')
+                        .conflicts_with_all(["compare", "changed", "isolate", "scaling"]),
+                )
+                .arg(
+                    arg!(--threshold <percent> "slowdown that counts as a regression with --compare")
+                        .value_parser(value_parser!(f64))
+                        .default_value("10"),
+                )
+                .arg(arg!(--quick "use a short time budget for a rough check").conflicts_with("precise"))
+                .arg(arg!(--precise "use a long time budget for publishing results"))
+                .arg(
+                    arg!(--"target-time" <seconds> "time to spend benchmarking each part")
+                        .value_parser(value_parser!(f64)),
+                )
+                .arg(
+                    arg!(--"min-iterations" <n> "run each part at least this many times")
+                        .value_parser(value_parser!(usize)),
+                )
+                .arg(
+                    arg!(--"max-iterations" <n> "run each part at most this many times")
+                        .value_parser(value_parser!(usize)),
+                )
+                .arg(
+                    arg!(--warmup <n> "untimed runs before measuring")
+                        .value_parser(value_parser!(usize)),
+                )
+                .arg(arg!(--isolate "benchmark every part in a separate process"))
+                .arg(
+                    arg!(--"pin-cpu" <core> "pin the isolated processes to a CPU core (Linux only)")
+                        .value_parser(value_parser!(usize))
+                        .requires("isolate"),
+                )
+                .args_conflicts_with_subcommands(true)
+                .subcommand(
+                    Command::new("history")
+                        .about("Show how a solver's benchmark changed across commits")
+                        .arg(arg!(<day> "which day to show").value_parser(value_parser!(Day)))
+                        .arg(arg!([part] "which part to show").value_parser(value_parser!(Part))),
+                )
+                .subcommand(
+                    Command::new("profile")
+                        .about("Run a solver under a sampling profiler and write a flamegraph")
+                        .arg(arg!(<day> "which day to profile").value_parser(value_parser!(Day)))
+                        .arg(
+                            arg!([part] "which part to profile, both if not given")
+                                .value_parser(value_parser!(Part)),
+                        )
+                        .arg(
+                            arg!(--duration <seconds> "how long to keep running the solver")
+                                .value_parser(value_parser!(f64))
+                                .default_value("5"),
+                        )
+                        .arg(
+                            arg!(--frequency <hz> "samples per second")
+                                .value_parser(value_parser!(i32))
+                                .default_value("999"),
+                        )
+                        .arg(
+                            arg!(--"output-dir" <dir> "where to write the flamegraphs and pprof files")
+                                .value_parser(value_parser!(PathBuf))
+                                .default_value("profiles"),
+                        )
+                        .arg(
+                            arg!(--"heap-profile" "record where the solver allocates and write a DHAT heap profile instead")
+                                .conflicts_with_all(["duration", "frequency"]),
+                        )
+                        .arg(
+                            arg!(--top <n> "how many allocation sites to list")
+                                .value_parser(value_parser!(usize))
+                                .default_value("10")
+                                .requires("heap-profile"),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("solve")
+                .about("Solve a day")
+                .arg(arg!([day] "which day to solve"))
+                .arg(arg!(--profile "profile the solvers and write flamegraphs to profiles/"))
+                .arg(
+                    arg!(--"heap-profile" "record where the solvers allocate and write DHAT heap profiles to profiles/")
+                        .conflicts_with("profile"),
+                )
+                .arg(
+                    arg!(--threads <count> "size of the rayon thread pool, or max")
+                        .value_parser(value_parser!(ThreadCount)),
+                )
+                .arg(format_arg())
+                .arg(
+                    arg!(--visualize [backend] "show the frames emitted by the solver")
+                        .value_parser(["terminal", "ppm", "png"])
+                        .default_missing_value("terminal"),
+                )
+                .arg(
+                    arg!(--"frames-dir" <dir> "where to write ppm/png frames")
+                        .value_parser(value_parser!(PathBuf))
+                        .default_value("frames"),
+                ),
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Check the answers of every solver against answers.toml")
+                .arg(arg!([day] "which day to verify"))
+                .arg(format_arg())
+                .arg(arg!(--save "record the answers that aren't known yet, marked as unverified")),
+        )
+        .subcommand(Command::new("status").about("Show the stars and solvers for each day"))
+        .subcommand(
+            Command::new(WORKER_COMMAND)
+                .hide(true)
+                .arg(arg!(<day>).value_parser(value_parser!(Day)))
+                .arg(arg!(<part>).value_parser(value_parser!(Part)))
+                .arg(arg!(--"pin-cpu" <core>).value_parser(value_parser!(usize))),
+        )
+        .get_matches();
+
+    if let Some(bench_args) = matches.subcommand_matches("bench") {
+        if let Some(history_args) = bench_args.subcommand_matches("history") {
+            print_benchmark_history(
+                *history_args.get_one::<Day>("day").unwrap(),
+                history_args.get_one::<Part>("part").copied(),
+            );
+            return Ok(());
+        }
+
+        if let Some(profile_args) = bench_args.subcommand_matches("profile") {
+            if profile_args.get_flag("heap-profile") {
+                return heap_profile_parts(
+                    &solvers,
+                    &puzzle_source,
+                    *profile_args.get_one::<Day>("day").unwrap(),
+                    profile_args.get_one::<Part>("part").copied(),
+                    &HeapProfileOptions {
+                        output_dir: profile_args
+                            .get_one::<PathBuf>("output-dir")
+                            .unwrap()
+                            .clone(),
+                        top: *profile_args.get_one::<usize>("top").unwrap(),
+                    },
+                );
+            }
+            let options = ProfileOptions {
+                duration: Duration::try_from_secs_f64(
+                    *profile_args.get_one::<f64>("duration").unwrap(),
+                )
+                .map_err(|e| e.to_string())?,
+                frequency: *profile_args.get_one::<i32>("frequency").unwrap(),
+                output_dir: profile_args
+                    .get_one::<PathBuf>("output-dir")
+                    .unwrap()
+                    .clone(),
+            };
+            return profile_parts(
+                &solvers,
+                &puzzle_source,
+                *profile_args.get_one::<Day>("day").unwrap(),
+                profile_args.get_one::<Part>("part").copied(),
+                &options,
+            );
+        }
+
+        let policy = get_benchmark_policy(bench_args)?;
+        log::info!("benchmark policy: {policy}");
+        progress::install_interrupt_handler().map_err(|e| e.to_string())?;
+        let isolation = bench_args.get_flag("isolate").then(|| IsolationOptions {
+            pin_cpu: bench_args.get_one::<usize>("pin-cpu").copied(),
+        });
+        let run = |days: &[Day]| {
+            benchmark_days(
+                &solvers,
+                &puzzle_source,
+                days.iter().copied(),
+                &policy,
+                isolation.as_ref(),
+            )
+        };
+
+        let format = get_format(bench_args);
+        // the scaling tables have no machine-readable form, and scripts couldn't parse them
+        if format != OutputFormat::Table
+            && (bench_args.contains_id("threads") || bench_args.get_flag("scaling"))
+        {
+            return Err(
+                "--threads and --scaling only print tables, so --format has to be table"
+                    .to_string(),
+            );
+        }
+        let day = bench_args
+            .get_one::<String>("day")
+            .map(|day| day.parse::<Day>().unwrap());
+
+        if let Some(thread_counts) = bench_args.get_many::<ThreadCount>("threads") {
+            let days = day.map_or_else(|| get_days_iter().collect(), |day| vec![day]);
+            let results = run_thread_scaling(
+                &solvers,
+                &puzzle_source,
+                days.into_iter(),
+                &thread_counts.copied().collect::<Vec<_>>(),
+                &policy,
+            )
+            .map_err(|e| format!("{e:#}"))?;
+            println!("{}", format_thread_scaling(&results));
+            return Ok(());
+        }
+
+        if bench_args.get_flag("scaling") {
+            let sizes = bench_args
+                .get_many::<usize>("sizes")
+                .map_or(DEFAULT_SIZES.to_vec(), |sizes| sizes.copied().collect());
+            let days = day.map_or_else(|| get_days_iter().collect(), |day| vec![day]);
+            let results = run_scaling(
+                &solvers,
+                &get_generators(),
+                days.into_iter(),
+                &sizes,
+                &policy,
+            );
+            if results.is_empty() {
+                return Err("none of the days have a #[generator]".to_string());
+            }
+            println!("{}", format_scaling(&results, &sizes));
+            return Ok(());
+        }
+
+        if bench_args.get_flag("compare") {
+            let days = day.map_or_else(|| get_days_iter().collect(), |day| vec![day]);
+            let current = run(&days)?;
+            let report = benchmark_report(&solvers, &days, &current);
+            print_report(&report, format, "bench");
+            write_html_report(bench_args, &report, &policy)?;
+            return compare_with_cached(
+                current,
+                *bench_args.get_one::<f64>("threshold").unwrap(),
+                &policy,
+                format,
+            );
+        }
+
+        // new results are merged into the cached ones, if they were measured the same way
+        let cached = get_cached_benchmarks(&policy).map(|mut cached| {
+            let stale_days = mark_stale(&mut cached, &puzzle_source);
+            (cached, stale_days)
+        });
+        let (days, mut benchmarks) = match (day, cached) {
+            (Some(day), Some((cached, _))) => (vec![day], cached),
+            // the cache was measured another way, but an explicit day still only runs that day
+            (Some(day), None) => (vec![day], HashMap::new()),
+            (None, Some((cached, stale_days))) if bench_args.get_flag("changed") => {
+                // days that were never benchmarked count as changed too
+                let days = get_days_iter()
+                    .filter(|day| (1..=2).any(|part| solvers.contains_key(&(*day, part))))
+                    .filter(|day| {
+                        stale_days.contains(day)
+                            || !(cached.contains_key(&(*day, 1)) || cached.contains_key(&(*day, 2)))
+                    })
+                    .collect::<Vec<_>>();
+                log::info!("benchmarking changed days: {days:?}");
+                (days, cached)
+            }
+            _ => (get_days_iter().collect(), HashMap::new()),
+        };
+        let new_results = run(&days)?;
+        append_benchmark_history(&new_results, &policy);
+        let report = benchmark_report(&solvers, &days, &new_results);
+        print_report(&report, format, "bench");
+        write_html_report(bench_args, &report, &policy)?;
+        if progress::interrupted() && benchmarks.is_empty() {
+            // a full run replaces the cached results, but not of the days it didn't get to
+            benchmarks = get_cached_benchmarks(&policy).unwrap_or_default();
+        }
+        benchmarks.extend(new_results);
+
+        save_cached_benchmarks(&benchmarks, &policy);
+        let readme_config = Config::load()
+            .map_err(|e| format!("failed to load the config: {e}"))?
+            .readme;
+        update_readme(
+            &get_all_cached_benchmarks(),
+            &Stars::load_offline(&puzzle_source),
+            &ReadmeOptions {
+                show_statistics: bench_args.get_flag("readme-stats"),
+                show_allocations: bench_args.get_flag("readme-allocs"),
+                combined: bench_args.get_flag("readme-combined"),
+                output: bench_args
+                    .get_one::<PathBuf>("readme-file")
+                    .cloned()
+                    .or(readme_config.output),
+                templates: readme_config.templates,
+            },
+        )
+        .map_err(|e| e.to_string())?;
+        if progress::interrupted() {
+            return Err("interrupted, only the parts that finished were saved".to_string());
+        }
+        Ok(())
+    } else if let Some(solve_args) = matches.subcommand_matches("solve") {
+        install_visualizer(solve_args)?;
+        if let Some(threads) = solve_args.get_one::<ThreadCount>("threads") {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads.resolve())
+                .build_global()
+                .map_err(|e| e.to_string())?;
+        }
+        let format = get_format(solve_args);
+        if solve_args.get_flag("heap-profile") {
+            let day = match solve_args.get_one::<String>("day") {
+                Some(day) => day.parse::<Day>().unwrap(),
+                None => get_last_day(&solvers).ok_or("no solved days".to_string())?,
+            };
+            return heap_profile_parts(
+                &solvers,
+                &puzzle_source,
+                day,
+                None,
+                &HeapProfileOptions::default(),
+            );
+        }
+        if solve_args.get_flag("profile") {
+            let day = match solve_args.get_one::<String>("day") {
+                Some(day) => day.parse::<Day>().unwrap(),
+                None => get_last_day(&solvers).ok_or("no solved days".to_string())?,
+            };
+            return profile_parts(
+                &solvers,
+                &puzzle_source,
+                day,
+                None,
+                &ProfileOptions::default(),
+            );
+        }
+        if let Some(day) = solve_args.get_one::<String>("day") {
+            solve_one(
+                &solvers,
+                &puzzle_source,
+                day.parse::<Day>().unwrap(),
+                format,
+            )
+        } else {
+            solve_latest(&solvers, &puzzle_source, format)
+        }
+    } else if let Some(verify_args) = matches.subcommand_matches("verify") {
+        if let Some(day) = verify_args.get_one::<String>("day") {
+            verify_answers(
+                &solvers,
+                &puzzle_source,
+                iter::once(day.parse::<Day>().unwrap()),
+                verify_args.get_flag("save"),
+                get_format(verify_args),
+            )
+        } else {
+            verify_answers(
+                &solvers,
+                &puzzle_source,
+                get_days_iter(),
+                verify_args.get_flag("save"),
+                get_format(verify_args),
+            )
+        }
+    } else if let Some(worker_args) = matches.subcommand_matches(WORKER_COMMAND) {
+        run_worker_process(
+            &solvers,
+            &puzzle_source,
+            *worker_args.get_one::<Day>("day").unwrap(),
+            *worker_args.get_one::<Part>("part").unwrap(),
+            worker_args.get_one::<usize>("pin-cpu").copied(),
+        )
+        .map_err(|e| format!("{e:#}"))
+    } else if matches.subcommand_matches("status").is_some() {
+        print_status(&solvers, &Stars::load(&puzzle_source));
+        Ok(())
+    } else {
+        solve_latest(&solvers, &puzzle_source, OutputFormat::Table)
+    }
+}
+
+fn format_arg() -> Arg {
+    arg!(--format <format> "output format")
+        .value_parser(["table", "json", "csv", "junit"])
+        .default_value("table")
+}
+
+fn get_format(args: &ArgMatches) -> OutputFormat {
+    args.get_one::<String>("format").unwrap().parse().unwrap()
+}
+
+/// Print the report in a machine-readable format. The table format is printed by each command.
+fn print_report(report: &Report, format: OutputFormat, name: &str) {
+    if let Some(output) = report.format(format, name) {
+        print!("{output}");
+    }
+}
+
+/// Every part with a solver in the given days, including the ones that turned out not to be solved
+fn benchmark_report(solvers: &SolverMap, days: &[Day], results: &BenchmarkMap) -> Report {
+    let parts = days
+        .iter()
+        .flat_map(|&day| [(day, 1), (day, 2)])
+        .filter(|key| solvers.contains_key(key))
+        .map(|(day, part)| match results.get(&(day, part)) {
+            Some(result) => PartReport::from_benchmark(day, part, result),
+            None => PartReport::new(day, part, None),
+        })
+        .collect();
+    Report::new(parts)
+}
+
+fn write_html_report(
+    bench_args: &ArgMatches,
+    report: &Report,
+    policy: &BenchmarkPolicy,
+) -> Result<(), String> {
+    let Some(path) = bench_args.get_one::<PathBuf>("html") else {
+        return Ok(());
+    };
+    fs::write(path, html_report(report, policy))
+        .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+    log::info!("wrote the HTML report to {}", path.display());
+    Ok(())
+}
+
+/// Profile one part, or every solved part of the day
+fn profile_parts(
+    solvers: &SolverMap,
+    puzzle_source: &impl PuzzleSource,
+    day: Day,
+    part: Option<Part>,
+    options: &ProfileOptions,
+) -> Result<(), String> {
+    let all_parts = part.is_none();
+    for part in part.map_or_else(|| vec![1, 2], |part| vec![part]) {
+        match profile_solver(solvers, puzzle_source, day, part, options) {
+            Ok(output) => println!(
+                "day {day} part {part}: {} runs, wrote {} and {}",
+                output.runs,
+                output.flamegraph.display(),
+                output.pprof.display()
+            ),
+            // without a part, skip the ones that aren't solved
+            Err(e) if all_parts => log::warn!("skipping day {day} part {part}: {e}"),
+            Err(e) => return Err(format!("{e:#}")),
+        }
+    }
+    Ok(())
+}
+
+/// Heap profile one part, or every solved part of the day, and list where they allocate the most
+fn heap_profile_parts(
+    solvers: &SolverMap,
+    puzzle_source: &impl PuzzleSource,
+    day: Day,
+    part: Option<Part>,
+    options: &HeapProfileOptions,
+) -> Result<(), String> {
+    let all_parts = part.is_none();
+    for part in part.map_or_else(|| vec![1, 2], |part| vec![part]) {
+        match heap_profile_solver(solvers, puzzle_source, day, part, options) {
+            Ok(profile) => {
+                println!("day {day} part {part}, top allocation sites by bytes:\n");
+                println!(
+                    "{}\n",
+                    format_allocation_sites(&profile.top_by_bytes(options.top))
+                );
+                println!("by number of allocations:\n");
+                println!(
+                    "{}\n",
+                    format_allocation_sites(&profile.top_by_blocks(options.top))
+                );
+                println!("wrote {}", profile.path.display());
+            }
+            // without a part, skip the ones that aren't solved
+            Err(e) if all_parts => log::warn!("skipping day {day} part {part}: {e}"),
+            Err(e) => return Err(format!("{e:#}")),
+        }
+    }
+    Ok(())
+}
+
+/// The preset picked on the command line, then the config file, then any options given explicitly
+fn get_benchmark_policy(bench_args: &ArgMatches) -> Result<BenchmarkPolicy, String> {
+    let policy = if bench_args.get_flag("quick") {
+        BenchmarkPolicy::quick()
+    } else if bench_args.get_flag("precise") {
+        BenchmarkPolicy::precise()
+    } else {
+        Config::load()
+            .map_err(|e| format!("failed to load the config: {e}"))?
+            .bench
+            .apply(BenchmarkPolicy::default())
+            .map_err(|e| e.to_string())?
+    };
+
+    BenchConfig {
+        target_time: bench_args.get_one::<f64>("target-time").copied(),
+        min_iterations: bench_args.get_one::<usize>("min-iterations").copied(),
+        max_iterations: bench_args.get_one::<usize>("max-iterations").copied(),
+        warmup_iterations: bench_args.get_one::<usize>("warmup").copied(),
+    }
+    .apply(policy)
+    .map_err(|e| e.to_string())
+}
+
+fn benchmark_days(
+    solvers: &SolverMap,
+    puzzle_source: &impl PuzzleSource,
+    days: impl Iterator<Item = Day>,
+    policy: &BenchmarkPolicy,
+    isolation: Option<&IsolationOptions>,
+) -> Result<BenchmarkMap, String> {
+    let days = days.collect::<Vec<_>>();
+    let parts = days
+        .iter()
+        .flat_map(|day| (1..=2).map(move |part| (*day, part)))
+        .filter(|key| solvers.contains_key(key))
+        .count();
+    let _progress = ProgressDisplay::start(parts, policy.target_time);
+    let days = days.into_iter();
+
+    match isolation {
+        Some(options) => {
+            run_isolated_benchmarks(solvers, days, policy, options).map_err(|e| format!("{e:#}"))
+        }
+        None => Ok(run_benchmarks(solvers, puzzle_source, days, policy)),
+    }
+}
+
+fn compare_with_cached(
+    current: BenchmarkMap,
+    threshold_percent: f64,
+    policy: &BenchmarkPolicy,
+    format: OutputFormat,
+) -> Result<(), String> {
+    let baseline = get_cached_benchmarks(policy)
+        .ok_or("there are no cached benchmarks for this CPU and policy to compare against")?;
+
+    let comparisons = compare_benchmarks(&baseline, &current, threshold_percent);
+    // keep stdout machine-readable when using another format
+    comparisons.iter().for_each(|c| match format {
+        OutputFormat::Table => println!("{c}"),
+        _ => log::info!("{c}"),
+    });
+
+    let regressions = comparisons.iter().filter(|c| c.regression).count();
+    if regressions > 0 {
+        Err(format!(
+            "{regressions} parts are more than {threshold_percent}% slower"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn print_benchmark_history(day: Day, part: Option<Part>) {
+    let history = get_benchmark_history();
+
+    for part in part.map_or(vec![1, 2], |part| vec![part]) {
+        let mut previous: Option<Duration> = None;
+        let rows = history
+            .iter()
+            .filter_map(|entry| Some((entry, entry.get(day, part)?)))
+            .map(|(entry, result)| {
+                let change = previous.map_or("-".to_string(), |previous| {
+                    format!(
+                        "{:+.1}%",
+                        (result.average_duration.as_secs_f64() / previous.as_secs_f64() - 1.0)
+                            * 100.0
+                    )
+                });
+                previous = Some(result.average_duration);
+
+                let commit = entry.commit.as_deref().map_or("-".to_string(), |commit| {
+                    format!(
+                        "{}{}",
+                        commit.get(..8).unwrap_or(commit),
+                        if entry.dirty { "*" } else { "" }
+                    )
+                });
+
+                [
+                    commit,
+                    format_timestamp(entry.timestamp),
+                    entry.cpu_name.clone(),
+                    format_duration(result.average_duration),
+                    format_memory(result.peak_memory),
+                    change,
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        println!("Day {day:02} part {part}\n");
+        if rows.is_empty() {
+            println!("no benchmarks recorded\n");
+        } else {
+            println!(
+                "{}\n",
+                format_table(
+                    &["Commit", "Date", "CPU", "Time", "Memory", "Change"],
+                    &rows
+                )
+            );
+        }
+    }
+}
+
+fn print_status(solvers: &SolverMap, stars: &Stars) {
+    let days = get_days_iter()
+        .map(|day| {
+            [
+                format!("{day:02}"),
+                stars.status(day, 1, solvers).to_string(),
+                stars.status(day, 2, solvers).to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    println!("{}\n", stars.star_road());
+    println!("{}", format_table(&["Day", "Part 1", "Part 2"], &days));
+}
+
+fn install_visualizer(solve_args: &ArgMatches) -> Result<(), String> {
+    let Some(backend) = solve_args.get_one::<String>("visualize") else {
+        return Ok(());
+    };
+    let frames_dir = solve_args.get_one::<PathBuf>("frames-dir").unwrap().clone();
+
+    match backend.as_str() {
+        "terminal" => {
+            visualize::install(Box::new(TerminalVisualizer::new(Duration::from_millis(30))))
+        }
+        "ppm" | "png" => {
+            let format = if backend == "ppm" {
+                ImageFormat::Ppm
+            } else {
+                ImageFormat::Png
+            };
+            let visualizer =
+                ImageSequenceVisualizer::new(frames_dir, format).map_err(|e| e.to_string())?;
+            visualize::install(Box::new(visualizer));
+        }
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+fn solve_latest(
+    solvers: &SolverMap,
+    puzzle_source: &impl PuzzleSource,
+    format: OutputFormat,
+) -> Result<(), String> {
+    solve_one(
+        solvers,
+        puzzle_source,
+        get_last_day(solvers).ok_or("no solved days".to_string())?,
+        format,
+    )
+}
+
+fn solve_one(
+    solver_map: &SolverMap,
+    puzzle_source: &impl PuzzleSource,
+    day: Day,
+    format: OutputFormat,
+) -> Result<(), String> {
+    let input = puzzle_source
+        .get_input(day)
+        .expect("failed to get puzzle input");
+    let reports = (1..=2 as Part)
+        .filter_map(|part| {
+            let solver = solver_map.get(&(day, part))?;
+            let start = Instant::now();
+            let answer = solver(&input);
+            Some(PartReport {
+                time: Some(start.elapsed()),
+                input_hash: Some(input.hash()),
+                ..PartReport::new(day, part, answer)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if format == OutputFormat::Table {
+        println!("Day {day}");
+        for part in 1..=2 {
+            println!(
+                "- part {part}: {}",
+                reports
+                    .iter()
+                    .find(|report| report.part == part)
+                    .and_then(|report| report.answer.clone())
+                    .unwrap_or("-".to_string()),
+            );
+        }
+    } else {
+        print_report(&Report::new(reports), format, "solve");
+    }
+
+    visualize::finish();
+
+    Ok(())
+}
+
+fn verify_answers(
+    solver_map: &SolverMap,
+    puzzle_source: &impl PuzzleSource,
+    days: impl Iterator<Item = Day>,
+    save: bool,
+    format: OutputFormat,
+) -> Result<(), String> {
+    let mut answers = AnswersFile::load()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+    let mut mismatches = 0;
+    let mut new_answers = 0;
+    let mut reports = vec![];
+
+    for day in days {
+        if !solver_map.contains_key(&(day, 1)) && !solver_map.contains_key(&(day, 2)) {
+            continue;
+        }
+
+        let input = puzzle_source
+            .get_input(day)
+            .expect("failed to get puzzle input");
+        let input_hash = input.hash();
+
+        for part in 1..=2 as Part {
+            let Some(solver) = solver_map.get(&(day, part)) else {
+                continue;
+            };
+
+            let start = Instant::now();
+            let answer = solver(&input);
+            let time = start.elapsed();
+
+            let verification = answers.verify(day, part, &input_hash, answer.clone());
+            if format == OutputFormat::Table {
+                println!("Day {day:02} part {part}: {verification}");
+            }
+            reports.push(PartReport {
+                input_hash: Some(input_hash.clone()),
+                time: Some(time),
+                verification: Some(verification.clone()),
+                ..PartReport::new(day, part, answer)
+            });
+
+            let unsaved = match verification {
+                Verification::Correct => None,
+                Verification::Mismatch { .. } => {
+                    mismatches += 1;
+                    None
+                }
+                Verification::Unknown { actual } => actual,
+                Verification::Unverified { saved, actual } => actual.filter(|a| *a != saved),
+            };
+            // nothing says these are right, so they're saved as unverified
+            if let Some(answer) = unsaved.filter(|_| save) {
+                answers.insert(KnownAnswer {
+                    day,
+                    part,
+                    input: Some(input_hash.clone()),
+                    answer,
+                    unverified: true,
+                });
+                new_answers += 1;
+            }
+        }
+    }
+
+    print_report(&Report::new(reports), format, "verify");
+
+    if new_answers > 0 {
+        answers.save().map_err(|e| e.to_string())?;
+        log::info!("saved {new_answers} new answers");
+    }
+
+    if mismatches > 0 {
+        Err(format!("{mismatches} answers did not match"))
+    } else {
+        Ok(())
+    }
+}
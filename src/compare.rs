@@ -0,0 +1,161 @@
+use crate::BenchmarkMap;
+use crate::aoc::{Day, Part};
+use crate::bench::{BenchmarkResults, format_duration};
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// Changes smaller than this are treated as noise
+const NOISE_THRESHOLD_PERCENT: f64 = 2.0;
+/// Roughly a 95% confidence level for Welch's t-test with a reasonable number of samples
+const T_CRITICAL: f64 = 2.0;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Change {
+    Faster,
+    Slower,
+    Unchanged,
+}
+
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub day: Day,
+    pub part: Part,
+    pub baseline: Duration,
+    pub current: Duration,
+    pub change_percent: f64,
+    pub change: Change,
+    /// Slower by more than the regression threshold
+    pub regression: bool,
+}
+
+/// Compare every part in `current` that also has a baseline result
+pub fn compare_benchmarks(
+    baseline: &BenchmarkMap,
+    current: &BenchmarkMap,
+    regression_threshold_percent: f64,
+) -> Vec<Comparison> {
+    current
+        .iter()
+        .sorted_by_key(|(k, _)| **k)
+        .filter_map(|(&(day, part), current)| {
+            let baseline = baseline.get(&(day, part))?;
+            let change_percent = (current.average_duration.as_secs_f64()
+                / baseline.average_duration.as_secs_f64()
+                - 1.0)
+                * 100.0;
+
+            let change = if change_percent.abs() < NOISE_THRESHOLD_PERCENT
+                || !is_significant(baseline, current)
+            {
+                Change::Unchanged
+            } else if change_percent < 0.0 {
+                Change::Faster
+            } else {
+                Change::Slower
+            };
+
+            Some(Comparison {
+                day,
+                part,
+                baseline: baseline.average_duration,
+                current: current.average_duration,
+                change_percent,
+                change,
+                regression: change == Change::Slower
+                    && change_percent > regression_threshold_percent,
+            })
+        })
+        .collect()
+}
+
+/// Welch's t-test on the mean durations. Results without enough samples to estimate their spread
+/// (e.g. very slow solvers that only ran once) are always considered significant.
+fn is_significant(a: &BenchmarkResults, b: &BenchmarkResults) -> bool {
+    if a.sample_count < 2 || b.sample_count < 2 {
+        return true;
+    }
+
+    let variance = |r: &BenchmarkResults| r.std_dev.as_secs_f64().powi(2) / r.sample_count as f64;
+    let standard_error = (variance(a) + variance(b)).sqrt();
+    if standard_error == 0.0 {
+        return a.average_duration != b.average_duration;
+    }
+
+    let t = (b.average_duration.as_secs_f64() - a.average_duration.as_secs_f64()) / standard_error;
+    t.abs() > T_CRITICAL
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let change = match self.change {
+            Change::Faster => "faster",
+            Change::Slower => "slower",
+            Change::Unchanged => "unchanged",
+        };
+        write!(
+            f,
+            "Day {:02} part {}: {} -> {} ({:+.1}%) {change}",
+            self.day,
+            self.part,
+            format_duration(self.baseline),
+            format_duration(self.current),
+            self.change_percent,
+        )?;
+        if self.regression {
+            write!(f, " REGRESSION")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn result(mean_ms: u64, std_dev_ms: u64, sample_count: usize) -> BenchmarkResults {
+        BenchmarkResults {
+            iterations: sample_count,
+            std_dev: Duration::from_millis(std_dev_ms),
+            sample_count,
+            samples: vec![],
+            ..BenchmarkResults::fixture(Duration::from_millis(mean_ms))
+        }
+    }
+
+    #[test]
+    fn test_compare_benchmarks() {
+        let baseline: BenchmarkMap = HashMap::from([
+            ((1, 1), result(100, 1, 100)),
+            ((1, 2), result(100, 1, 100)),
+            ((2, 1), result(100, 50, 5)),
+            ((3, 1), result(100, 1, 100)),
+        ]);
+        let current: BenchmarkMap = HashMap::from([
+            ((1, 1), result(50, 1, 100)),
+            ((1, 2), result(200, 1, 100)),
+            ((2, 1), result(120, 50, 5)),
+            ((3, 1), result(105, 1, 100)),
+            ((4, 1), result(100, 1, 100)),
+        ]);
+
+        let comparisons = compare_benchmarks(&baseline, &current, 10.0);
+        let changes = comparisons
+            .iter()
+            .map(|c| ((c.day, c.part), c.change, c.regression))
+            .collect_vec();
+
+        assert_eq!(
+            changes,
+            vec![
+                ((1, 1), Change::Faster, false),
+                ((1, 2), Change::Slower, true),
+                // too noisy to tell
+                ((2, 1), Change::Unchanged, false),
+                // slower, but under the regression threshold
+                ((3, 1), Change::Slower, false),
+            ]
+        );
+    }
+}
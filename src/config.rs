@@ -0,0 +1,106 @@
+use crate::bench::BenchmarkPolicy;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env::current_dir;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Optional settings, stored in `aoc.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub bench: BenchConfig,
+    pub readme: ReadmeConfig,
+}
+
+/// Overrides for the benchmark policy. Anything that isn't set keeps the value of the preset.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BenchConfig {
+    /// In seconds
+    pub target_time: Option<f64>,
+    pub min_iterations: Option<usize>,
+    pub max_iterations: Option<usize>,
+    pub warmup_iterations: Option<usize>,
+}
+
+/// Where the benchmark results are written, and how
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReadmeConfig {
+    /// Write to this file, for example `BENCHMARKS.md`, instead of `README.md`
+    pub output: Option<PathBuf>,
+    /// Templates for the marker blocks, by block name
+    pub templates: HashMap<String, String>,
+}
+
+pub fn get_config_path() -> PathBuf {
+    current_dir().unwrap().join("aoc.toml")
+}
+
+impl Config {
+    /// Load the config file, or the defaults if there isn't one
+    pub fn load() -> anyhow::Result<Self> {
+        let path = get_config_path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+}
+
+impl BenchConfig {
+    pub fn apply(&self, policy: BenchmarkPolicy) -> anyhow::Result<BenchmarkPolicy> {
+        let target_time = match self.target_time {
+            Some(seconds) => Duration::try_from_secs_f64(seconds)?,
+            None => policy.target_time,
+        };
+        let policy = BenchmarkPolicy {
+            target_time,
+            min_iterations: self.min_iterations.unwrap_or(policy.min_iterations),
+            max_iterations: self.max_iterations.unwrap_or(policy.max_iterations),
+            warmup_iterations: self.warmup_iterations.unwrap_or(policy.warmup_iterations),
+        };
+
+        anyhow::ensure!(
+            policy.max_iterations >= 1,
+            "the maximum number of iterations must be at least 1"
+        );
+        anyhow::ensure!(
+            policy.min_iterations <= policy.max_iterations,
+            "the minimum number of iterations is more than the maximum"
+        );
+        Ok(policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_bench_config() {
+        let config: Config =
+            toml::from_str("[bench]\ntarget_time = 1.5\nmin_iterations = 3").unwrap();
+        let policy = config.bench.apply(BenchmarkPolicy::quick()).unwrap();
+
+        assert_eq!(
+            policy,
+            BenchmarkPolicy {
+                target_time: Duration::from_millis(1500),
+                min_iterations: 3,
+                ..BenchmarkPolicy::quick()
+            }
+        );
+
+        let invalid = BenchConfig {
+            min_iterations: Some(10),
+            max_iterations: Some(5),
+            ..BenchConfig::default()
+        };
+        assert!(invalid.apply(BenchmarkPolicy::default()).is_err());
+    }
+}
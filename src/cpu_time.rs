@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+/// CPU time spent so far, in user and kernel mode
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct CpuTime {
+    pub user: Duration,
+    pub system: Duration,
+}
+
+impl CpuTime {
+    /// The whole process, including every thread it started
+    pub fn process() -> Self {
+        get_rusage(Scope::Process)
+    }
+
+    /// Only the calling thread
+    pub fn thread() -> Self {
+        get_rusage(Scope::Thread)
+    }
+
+    pub fn since(&self, start: &CpuTime) -> CpuTime {
+        CpuTime {
+            user: self.user.saturating_sub(start.user),
+            system: self.system.saturating_sub(start.system),
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.user + self.system
+    }
+
+    pub fn per_run(&self, runs: usize) -> CpuTime {
+        let runs = runs.max(1) as u32;
+        CpuTime {
+            user: self.user / runs,
+            system: self.system / runs,
+        }
+    }
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+enum Scope {
+    Process,
+    Thread,
+}
+
+#[cfg(target_os = "linux")]
+fn get_rusage(scope: Scope) -> CpuTime {
+    let who = match scope {
+        Scope::Process => libc::RUSAGE_SELF,
+        Scope::Thread => libc::RUSAGE_THREAD,
+    };
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrusage(who, &mut usage) } != 0 {
+        return CpuTime::default();
+    }
+
+    let duration = |time: libc::timeval| {
+        Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64)
+    };
+    CpuTime {
+        user: duration(usage.ru_utime),
+        system: duration(usage.ru_stime),
+    }
+}
+
+/// Only measured on Linux, everywhere else it's always zero
+#[cfg(not(target_os = "linux"))]
+fn get_rusage(_scope: Scope) -> CpuTime {
+    CpuTime::default()
+}
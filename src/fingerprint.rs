@@ -0,0 +1,100 @@
+use crate::BenchmarkMap;
+use crate::aoc::{Day, PuzzleSource};
+use crate::solutions::get_day_sources;
+use crate::utils::hex_encode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+
+/// What a benchmark was built from. If any of it changes, the cached result is out of date.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// Hash of the day's source file
+    pub source: String,
+    pub rustc_version: String,
+    pub profile: String,
+}
+
+impl Fingerprint {
+    /// The fingerprint of a day in the running binary
+    pub fn current(day: Day) -> Self {
+        let source = get_day_sources().get(&day).copied().unwrap_or_default();
+        Self {
+            source: hex_encode(&Sha256::digest(source.as_bytes())[..10]),
+            rustc_version: env!("AOC_RUSTC_VERSION").to_string(),
+            profile: env!("AOC_BUILD_PROFILE").to_string(),
+        }
+    }
+}
+
+/// Mark every cached result that was measured with a different source, input, compiler or profile as
+/// stale, and return the days that have at least one stale result
+pub fn mark_stale(
+    benchmarks: &mut BenchmarkMap,
+    puzzle_source: &impl PuzzleSource,
+) -> BTreeSet<Day> {
+    let mut input_hashes = HashMap::new();
+    let mut stale_days = BTreeSet::new();
+
+    for (&(day, part), result) in benchmarks.iter_mut() {
+        let input_hash = input_hashes
+            .entry(day)
+            .or_insert_with(|| puzzle_source.get_input(day).ok().map(|input| input.hash()));
+
+        let reason = if result.fingerprint.is_none() || result.input_hash.is_none() {
+            Some("it was measured before fingerprints were recorded")
+        } else if result.fingerprint.as_ref() != Some(&Fingerprint::current(day)) {
+            Some("the source, compiler or profile changed")
+        } else if result.input_hash != *input_hash {
+            Some("the input changed")
+        } else {
+            None
+        };
+
+        result.stale = reason.is_some();
+        if let Some(reason) = reason {
+            log::warn!("the cached result for day {day} part {part} is stale: {reason}");
+            stale_days.insert(day);
+        }
+    }
+
+    stale_days
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc::FixedDataSource;
+    use crate::bench::BenchmarkResults;
+    use std::time::Duration;
+
+    #[test]
+    fn test_mark_stale() {
+        let unknown = BenchmarkResults::fixture(Duration::from_millis(1));
+        let puzzle_source = FixedDataSource {
+            lines: vec!["1".to_string()],
+        };
+        let input_hash = puzzle_source.get_input(1).unwrap().hash();
+
+        let result = |day: Day, input_hash: &str| {
+            let mut result = unknown.clone();
+            result.fingerprint = Some(Fingerprint::current(day));
+            result.input_hash = Some(input_hash.to_string());
+            result
+        };
+        let mut changed_source = result(2, &input_hash);
+        changed_source.fingerprint.as_mut().unwrap().source = "old".to_string();
+
+        let mut benchmarks = BenchmarkMap::from([
+            ((1, 1), result(1, &input_hash)),
+            ((2, 1), changed_source),
+            ((3, 1), result(3, "old")),
+            ((4, 1), unknown.clone()),
+        ]);
+        let stale = mark_stale(&mut benchmarks, &puzzle_source);
+
+        assert_eq!(stale, BTreeSet::from([2, 3, 4]));
+        assert!(!benchmarks[&(1, 1)].stale);
+        assert!(benchmarks[&(2, 1)].stale);
+    }
+}
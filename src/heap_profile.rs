@@ -0,0 +1,274 @@
+use crate::RunError;
+use crate::alloc;
+use crate::aoc::{Day, Part, PuzzleSource, SolverMap};
+use crate::bench::{format_count, format_memory};
+use crate::readme::format_table;
+use itertools::Itertools;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct HeapProfileOptions {
+    pub output_dir: PathBuf,
+    /// How many allocation sites to list
+    pub top: usize,
+}
+
+impl Default for HeapProfileOptions {
+    fn default() -> Self {
+        Self {
+            output_dir: PathBuf::from("profiles"),
+            top: 10,
+        }
+    }
+}
+
+/// Everything allocated from one location, merged from every call stack that goes through it
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AllocationSite {
+    /// The innermost frame in a solution, or else the innermost frame outside of the allocator
+    pub location: String,
+    pub bytes: u64,
+    pub blocks: u64,
+    /// The most bytes one of the call stacks had allocated at the same time
+    pub max_bytes: usize,
+}
+
+/// What [`heap_profile_solver`] wrote and found
+#[derive(Debug, Clone)]
+pub struct HeapProfile {
+    /// Open it with the DHAT viewer, https://nnethercote.github.io/dh_view/dh_view.html
+    pub path: PathBuf,
+    pub sites: Vec<AllocationSite>,
+}
+
+impl HeapProfile {
+    pub fn top_by_bytes(&self, count: usize) -> Vec<&AllocationSite> {
+        self.sites
+            .iter()
+            .sorted_by_key(|site| std::cmp::Reverse(site.bytes))
+            .take(count)
+            .collect()
+    }
+
+    pub fn top_by_blocks(&self, count: usize) -> Vec<&AllocationSite> {
+        self.sites
+            .iter()
+            .sorted_by_key(|site| std::cmp::Reverse(site.blocks))
+            .take(count)
+            .collect()
+    }
+}
+
+/// Run a solver once with every allocation recorded along with its backtrace, then write a DHAT
+/// heap profile to the output directory and read the allocation sites back from it
+pub fn heap_profile_solver(
+    solver_map: &SolverMap,
+    puzzle_source: &impl PuzzleSource,
+    day: Day,
+    part: Part,
+    options: &HeapProfileOptions,
+) -> anyhow::Result<HeapProfile> {
+    let solver = solver_map
+        .get(&(day, part))
+        .ok_or(RunError::NoSolver { day, part })?;
+    let input = puzzle_source.get_input(day)?;
+    // resolving the backtraces is slow, don't bother for a part that gives up immediately
+    solver(&input).ok_or(RunError::NotSolved { day, part })?;
+
+    fs::create_dir_all(&options.output_dir)?;
+    let path = options
+        .output_dir
+        .join(format!("day{day:02}-part{part}-heap.json"));
+
+    alloc::set_heap_profiling(true);
+    // the allocator, `RawVec` and hashbrown frames alone take up dhat's default depth
+    let profiler = dhat::Profiler::builder()
+        .file_name(&path)
+        .trim_backtraces(None)
+        .build();
+    std::hint::black_box(solver(std::hint::black_box(&input)));
+    drop(profiler);
+    alloc::set_heap_profiling(false);
+
+    Ok(HeapProfile {
+        sites: read_allocation_sites(&path)?,
+        path,
+    })
+}
+
+/// The parts of DHAT's file format needed to find the allocation sites
+#[derive(Deserialize)]
+struct DhatFile {
+    pps: Vec<ProgramPoint>,
+    ftbl: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ProgramPoint {
+    tb: u64,
+    tbk: u64,
+    #[serde(default)]
+    mb: usize,
+    /// Indices into the frame table, innermost first
+    fs: Vec<usize>,
+}
+
+fn read_allocation_sites(path: &Path) -> anyhow::Result<Vec<AllocationSite>> {
+    let file: DhatFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let sites = file.pps.into_iter().map(|point| {
+        let frames = point
+            .fs
+            .iter()
+            .filter_map(|&i| file.ftbl.get(i))
+            .map(|frame| strip_address(frame))
+            .collect_vec();
+        let location = frames
+            .iter()
+            // dhat shortens paths to their last three components
+            .find(|frame| frame.contains("(src/solutions/"))
+            .or_else(|| frames.iter().find(|frame| !is_allocator_frame(frame)))
+            .map_or_else(|| "???".to_string(), |frame| frame.to_string());
+        AllocationSite {
+            location,
+            bytes: point.tb,
+            blocks: point.tbk,
+            max_bytes: point.mb,
+        }
+    });
+
+    // call stacks that only differ further out end up at the same location
+    Ok(sites
+        .into_grouping_map_by(|site| site.location.clone())
+        .reduce(|merged, _, site| AllocationSite {
+            bytes: merged.bytes + site.bytes,
+            blocks: merged.blocks + site.blocks,
+            max_bytes: merged.max_bytes.max(site.max_bytes),
+            ..merged
+        })
+        .into_values()
+        .collect())
+}
+
+/// Frames of dhat, the standard library's allocation functions and `CountingAlloc`, which every
+/// allocation goes through
+fn is_allocator_frame(frame: &str) -> bool {
+    [
+        "dhat",
+        "alloc::",
+        "__rust_alloc",
+        "(alloc/src/",
+        "/src/alloc.rs:",
+    ]
+    .iter()
+    .any(|pattern| frame.contains(pattern))
+}
+
+/// Frames look like `0x55d0c1a2b3c4: connect_until (src/solutions/day08.rs:52:20)`
+fn strip_address(frame: &str) -> &str {
+    frame
+        .split_once(": ")
+        .filter(|(address, _)| address.starts_with("0x"))
+        .map_or(frame, |(_, symbol)| symbol)
+}
+
+/// A table of the allocation sites, in the order given
+pub fn format_allocation_sites(sites: &[&AllocationSite]) -> String {
+    let rows = sites
+        .iter()
+        .map(|site| {
+            [
+                format_memory(site.bytes as usize),
+                format_count(site.blocks as usize),
+                format_memory(site.max_bytes),
+                format!("`{}`", site.location),
+            ]
+        })
+        .collect_vec();
+
+    format_table(&["Bytes", "Allocations", "Peak", "Location"], &rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc::FixedDataSource;
+    use crate::solutions::get_solvers;
+
+    #[test]
+    fn test_read_allocation_sites() {
+        let json = r#"{
+            "dhatFileVersion": 2, "mode": "rust-heap", "verb": "Allocated",
+            "bklt": true, "bkacc": false, "tu": "µs", "Mtu": "s", "tuth": 10,
+            "cmd": "aoc-2025", "pid": 1, "tg": 10, "te": 20,
+            "pps": [
+                {"tb": 1000, "tbk": 2, "tl": 5, "mb": 600, "mbk": 1, "gb": 600, "gbk": 1,
+                 "eb": 0, "ebk": 0, "fs": [1, 2, 3]},
+                {"tb": 24, "tbk": 1, "tl": 5, "mb": 24, "mbk": 1, "gb": 0, "gbk": 0,
+                 "eb": 0, "ebk": 0, "fs": [5, 2, 4]},
+                {"tb": 48, "tbk": 6, "tl": 5, "mb": 16, "mbk": 2, "gb": 8, "gbk": 1,
+                 "eb": 0, "ebk": 0, "fs": [5, 1, 4]}
+            ],
+            "ftbl": [
+                "[root]",
+                "0x1: finish_grow (alloc/src/raw_vec.rs:1:1)",
+                "0x2: parse (src/solutions/day08.rs:12:5)",
+                "0x3: heap_profile_solver<aoc_2025::inputs::CachedOnlinePuzzleSource> (crate/src/heap_profile.rs:86:26)",
+                "0x4: lang_start (std/src/rt.rs:1:1)",
+                "0x5: <dhat::Alloc as GlobalAlloc>::alloc (dhat-0.3.3/src/lib.rs:1:1)"
+            ]
+        }"#;
+        let path = std::env::temp_dir().join(format!("aoc-heap-{}.json", std::process::id()));
+        fs::write(&path, json).unwrap();
+
+        let profile = HeapProfile {
+            sites: read_allocation_sites(&path).unwrap(),
+            path: path.clone(),
+        };
+        fs::remove_file(path).unwrap();
+
+        // the first two call stacks both allocate in `parse`
+        assert_eq!(profile.sites.len(), 2);
+        let by_bytes = profile.top_by_bytes(1);
+        assert_eq!(
+            by_bytes,
+            vec![&AllocationSite {
+                location: "parse (src/solutions/day08.rs:12:5)".to_string(),
+                bytes: 1024,
+                blocks: 3,
+                max_bytes: 600,
+            }]
+        );
+        // without a solution frame, the allocator's frames are skipped
+        let by_blocks = profile.top_by_blocks(1);
+        assert_eq!(by_blocks[0].location, "lang_start (std/src/rt.rs:1:1)");
+        assert_eq!(by_blocks[0].blocks, 6);
+    }
+
+    #[test]
+    fn test_heap_profile_solver() {
+        let puzzle_source = FixedDataSource {
+            lines: ["L68", "L30", "R48", "L5", "R60"]
+                .map(str::to_string)
+                .to_vec(),
+        };
+        let options = HeapProfileOptions {
+            output_dir: std::env::temp_dir().join(format!("aoc-heap-{}", std::process::id())),
+            ..HeapProfileOptions::default()
+        };
+
+        let profile = heap_profile_solver(&get_solvers(), &puzzle_source, 1, 1, &options).unwrap();
+        fs::remove_dir_all(&options.output_dir).unwrap();
+
+        // other tests allocate at the same time, but day 1's own allocations are in there too
+        assert!(
+            profile
+                .sites
+                .iter()
+                .any(|site| site.location.contains("(src/solutions/day01.rs:")),
+            "{:#?}",
+            profile.top_by_bytes(options.top)
+        );
+    }
+}
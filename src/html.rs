@@ -0,0 +1,261 @@
+use crate::bench::{
+    BenchmarkPolicy, BenchmarkResults, format_count, format_duration, format_memory,
+};
+use crate::report::{PartReport, Report, xml_escape};
+use crate::utils::format_timestamp;
+use std::fmt::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const HISTOGRAM_BINS: usize = 30;
+const HISTOGRAM_WIDTH: f64 = 320.0;
+const HISTOGRAM_HEIGHT: f64 = 80.0;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; }
+th, td { padding: 4px 10px; border-bottom: 1px solid #ddd; text-align: right; }
+th { cursor: pointer; user-select: none; background: #f4f4f4; }
+th[data-order=asc]::after { content: \" ▲\"; }
+th[data-order=desc]::after { content: \" ▼\"; }
+td.text, th.text { text-align: left; }
+.distributions { display: flex; flex-wrap: wrap; gap: 1.5em; }
+figure { margin: 0; }
+figcaption { font-size: 0.9em; }
+";
+
+/// Sorts a table by the clicked column, using each cell's `data-sort` value if it has one
+const SORT_SCRIPT: &str = "
+document.querySelectorAll(\"th\").forEach((th, column) => th.addEventListener(\"click\", () => {
+  const tbody = th.closest(\"table\").tBodies[0];
+  const ascending = th.dataset.order !== \"asc\";
+  th.closest(\"tr\").querySelectorAll(\"th\").forEach(other => delete other.dataset.order);
+  th.dataset.order = ascending ? \"asc\" : \"desc\";
+  const key = row => row.cells[column].dataset.sort ?? row.cells[column].textContent;
+  const rows = [...tbody.rows].sort((a, b) => {
+    const [x, y] = [key(a), key(b)];
+    const order = isNaN(x - y) ? x.localeCompare(y) : x - y;
+    return ascending ? order : -order;
+  });
+  tbody.append(...rows);
+}));
+";
+
+/// A table cell that sorts by a number rather than its text
+fn numeric_cell(text: &str, value: impl Into<u128>) -> String {
+    format!(
+        "<td data-sort=\"{}\">{}</td>",
+        value.into(),
+        xml_escape(text)
+    )
+}
+
+fn duration_cell(duration: Duration) -> String {
+    numeric_cell(&format_duration(duration), duration.as_nanos())
+}
+
+fn benchmark_cells(result: Option<&BenchmarkResults>) -> String {
+    let Some(result) = result else {
+        // sorts below every measured part
+        return "<td data-sort=\"-1\">-</td>".repeat(12);
+    };
+    [
+        duration_cell(result.average_duration),
+        duration_cell(result.median_duration),
+        duration_cell(result.min_duration),
+        duration_cell(result.p95_duration),
+        duration_cell(result.std_dev),
+        duration_cell(result.cpu_time()),
+        duration_cell(result.thread_cpu),
+        numeric_cell(&result.iterations.to_string(), result.iterations as u128),
+        numeric_cell(
+            &format_memory(result.peak_memory),
+            result.peak_memory as u128,
+        ),
+        numeric_cell(&format_memory(result.peak_stack), result.peak_stack as u128),
+        numeric_cell(
+            &format_count(result.allocations),
+            result.allocations as u128,
+        ),
+        numeric_cell(
+            &format_memory(result.allocated_bytes),
+            result.allocated_bytes as u128,
+        ),
+    ]
+    .concat()
+}
+
+fn part_row(part: &PartReport) -> String {
+    format!(
+        "<tr><td class=\"text\" data-sort=\"{day}\"><a href=\"src/solutions/day{day:02}.rs\">{day:02}</a></td>\
+         <td data-sort=\"{}\">{}</td><td class=\"text\">{}</td><td class=\"text\">{}</td>{}</tr>\n",
+        part.part,
+        part.part,
+        part.status(),
+        xml_escape(part.answer.as_deref().unwrap_or("")),
+        benchmark_cells(part.benchmark.as_ref()),
+        day = part.day,
+    )
+}
+
+/// An SVG histogram of the time per iteration of every sample, between the fastest and slowest one
+fn histogram(samples: &[Duration]) -> String {
+    let min = samples
+        .iter()
+        .min()
+        .copied()
+        .unwrap_or_default()
+        .as_secs_f64();
+    let max = samples
+        .iter()
+        .max()
+        .copied()
+        .unwrap_or_default()
+        .as_secs_f64();
+    let bin_width = (max - min) / HISTOGRAM_BINS as f64;
+
+    let mut counts = [0usize; HISTOGRAM_BINS];
+    for sample in samples {
+        let bin = if bin_width > 0.0 {
+            ((sample.as_secs_f64() - min) / bin_width) as usize
+        } else {
+            0
+        };
+        counts[bin.min(HISTOGRAM_BINS - 1)] += 1;
+    }
+
+    let highest = counts.iter().max().copied().unwrap_or_default().max(1);
+    let bar_width = HISTOGRAM_WIDTH / HISTOGRAM_BINS as f64;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{HISTOGRAM_WIDTH}\" height=\"{HISTOGRAM_HEIGHT}\">"
+    );
+    for (bin, &count) in counts.iter().enumerate().filter(|(_, count)| **count > 0) {
+        let height = count as f64 / highest as f64 * HISTOGRAM_HEIGHT;
+        let from = Duration::from_secs_f64(min + bin as f64 * bin_width);
+        let _ = write!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{height:.1}\" fill=\"#4e79a7\">\
+             <title>{count} samples from {}</title></rect>",
+            bin as f64 * bar_width,
+            HISTOGRAM_HEIGHT - height,
+            bar_width - 1.0,
+            format_duration(from)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn distribution(part: &PartReport) -> Option<String> {
+    let result = part.benchmark.as_ref()?;
+    if result.samples.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "<figure>{}<figcaption>Day {} part {}: {} samples, {} to {} (median {})</figcaption></figure>\n",
+        histogram(&result.samples),
+        part.day,
+        part.part,
+        result.samples.len(),
+        format_duration(result.min_duration),
+        format_duration(result.samples.iter().max().copied().unwrap_or_default()),
+        format_duration(result.median_duration),
+    ))
+}
+
+/// A static page with everything measured in a benchmark run, with no external dependencies
+pub fn html_report(report: &Report, policy: &BenchmarkPolicy) -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let total = report
+        .parts
+        .iter()
+        .filter_map(PartReport::duration)
+        .sum::<Duration>();
+
+    let mut html = String::new();
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Benchmark report</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>Benchmark report</h1>"
+    );
+    let _ = writeln!(
+        html,
+        "<h2>Environment</h2>\n<ul>\n<li>CPU: {}</li>\n<li>Compiler: {}</li>\n\
+         <li>Profile: {}</li>\n<li>Policy: {}</li>\n<li>Generated: {}</li>\n\
+         <li>Total time: {}</li>\n</ul>",
+        xml_escape(&report.cpu),
+        xml_escape(env!("AOC_RUSTC_VERSION")),
+        env!("AOC_BUILD_PROFILE"),
+        xml_escape(&policy.to_string()),
+        format_timestamp(timestamp),
+        format_duration(total),
+    );
+
+    html.push_str(
+        "<h2>Results</h2>\n<table>\n<thead><tr><th class=\"text\">Day</th><th>Part</th>\
+         <th class=\"text\">Status</th><th class=\"text\">Answer</th><th>Average</th><th>Median</th>\
+         <th>Min</th><th>p95</th><th>±</th><th>CPU</th><th>Thread CPU</th><th>Iterations</th><th>Peak memory</th><th>Stack</th>\
+         <th>Allocations</th><th>Allocated</th></tr></thead>\n<tbody>\n",
+    );
+    for part in &report.parts {
+        html.push_str(&part_row(part));
+    }
+    html.push_str("</tbody>\n</table>\n");
+
+    html.push_str("<h2>Sample distributions</h2>\n<div class=\"distributions\">\n");
+    for distribution in report.parts.iter().filter_map(distribution) {
+        html.push_str(&distribution);
+    }
+    let _ = writeln!(
+        html,
+        "</div>\n<script>{SORT_SCRIPT}</script>\n</body>\n</html>"
+    );
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_report() {
+        let policy = BenchmarkPolicy::quick();
+        let result = BenchmarkResults::fixture(Duration::from_millis(1));
+        let report = Report {
+            cpu: "Test <CPU>".to_string(),
+            parts: vec![
+                PartReport::from_benchmark(3, 1, &result),
+                PartReport::new(3, 2, None),
+            ],
+        };
+        let html = html_report(&report, &policy);
+
+        assert!(html.contains("<li>CPU: Test &lt;CPU&gt;</li>"));
+        assert!(html.contains("<a href=\"src/solutions/day03.rs\">03</a>"));
+        assert_eq!(html.matches("<tr><td").count(), 2);
+        // only the benchmarked part has a distribution
+        assert_eq!(html.matches("<figure>").count(), 1);
+        assert!(html.contains(&format!("{} samples", result.samples.len())));
+    }
+
+    #[test]
+    fn test_histogram_bins() {
+        let samples = [10, 10, 10, 20, 40].map(Duration::from_nanos);
+        let svg = histogram(&samples);
+
+        assert!(svg.contains("<title>3 samples from 10ns</title>"));
+        // the slowest sample falls in the last bin instead of past it
+        assert!(svg.contains("<title>1 samples from 39ns</title>"));
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert_eq!(
+            histogram(&[Duration::from_nanos(5)])
+                .matches("<rect")
+                .count(),
+            1
+        );
+    }
+}
@@ -1,8 +1,10 @@
-use crate::aoc::{CURRENT_YEAR, Day, PuzzleInput, PuzzleSource};
+use crate::aoc::{CURRENT_YEAR, Day, Part, PuzzleInput, PuzzleSource};
+use crate::stars::{StarSource, parse_calendar_stars};
+use crate::utils::hex_encode;
 use directories::ProjectDirs;
 use reqwest::header::COOKIE;
 use sha2::{Digest, Sha256};
-use std::fmt::Write;
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 struct Config {
@@ -15,13 +17,6 @@ pub struct CachedOnlinePuzzleSource {
     config: Config,
 }
 
-fn hex_encode(bytes: &[u8]) -> String {
-    bytes.iter().fold(String::new(), |mut output, b| {
-        let _ = write!(output, "{b:02x}");
-        output
-    })
-}
-
 impl CachedOnlinePuzzleSource {
     fn new_with_default_directory(config: Config) -> Result<Self, OnlinePuzzleSourceCreateError> {
         let project_dirs = ProjectDirs::from("", "", env!("CARGO_PKG_NAME")).unwrap();
@@ -92,6 +87,25 @@ impl CachedOnlinePuzzleSource {
         }
     }
 
+    fn get_calendar_path(&self) -> PathBuf {
+        self.cache_directory.join("calendar.html")
+    }
+
+    fn get_calendar_from_api(&self) -> Result<String, PuzzleInputApiError> {
+        let url = format!("https://adventofcode.com/{}", self.config.year);
+        let client = reqwest::blocking::Client::new();
+        let response = client
+            .get(&url)
+            .header(COOKIE, format!("session={}", self.config.api_token))
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| PuzzleInputApiError::ApiError(e.to_string()))?;
+
+        response
+            .text()
+            .map_err(|e| PuzzleInputApiError::ApiError(e.to_string()))
+    }
+
     fn download_and_cache(&self, day: Day) -> Result<String, PuzzleInputSaveError> {
         let data = self.get_input_from_api(day)?;
 
@@ -124,6 +138,39 @@ impl PuzzleSource for CachedOnlinePuzzleSource {
     }
 }
 
+impl StarSource for CachedOnlinePuzzleSource {
+    fn get_stars(&self) -> anyhow::Result<HashSet<(Day, Part)>> {
+        let path = self.get_calendar_path();
+
+        // stars change over time, so the cached calendar is only used when offline
+        let calendar = match self.get_calendar_from_api() {
+            Ok(calendar) => {
+                std::fs::write(&path, &calendar)?;
+                calendar
+            }
+            Err(e) => {
+                log::warn!("failed to download calendar, using the cached one: {e}");
+                std::fs::read_to_string(&path)?
+            }
+        };
+
+        Ok(parse_calendar_stars(&calendar))
+    }
+
+    fn get_cached_stars(&self) -> anyhow::Result<Option<HashSet<(Day, Part)>>> {
+        let path = self.get_calendar_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(parse_calendar_stars(&std::fs::read_to_string(path)?)))
+    }
+
+    fn get_cached_input_hash(&self, day: Day) -> Option<String> {
+        let contents = std::fs::read_to_string(self.get_day_path(day)).ok()?;
+        Some(PuzzleInput::from(contents.as_str()).hash())
+    }
+}
+
 const DAY_NOT_UNLOCKED_START: &str =
     "Please don't repeatedly request this endpoint before it unlocks!";
 
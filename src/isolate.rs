@@ -0,0 +1,223 @@
+use crate::aoc::{Day, Part, PuzzleSource, SolverMap};
+use crate::bench::{BenchmarkPolicy, BenchmarkResults};
+use crate::{BenchmarkMap, RunError, benchmark_solver, log_day_results, progress};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env::current_exe;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// The hidden subcommand the child processes run
+pub const WORKER_COMMAND: &str = "bench-worker";
+/// Solvers might print to stdout too, so the results start after this line
+const RESULTS_MARKER: &str = "# bench-worker results";
+
+#[derive(Debug, Default, Clone)]
+pub struct IsolationOptions {
+    /// Pin every child process to this CPU core
+    pub pin_cpu: Option<usize>,
+}
+
+/// What a worker sends back on stdout
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WorkerOutput {
+    /// Missing if the part isn't solved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<BenchmarkResults>,
+    /// The samples are skipped when serializing the results, so they're sent separately
+    #[serde(default)]
+    samples_ns: Vec<u64>,
+    /// Also skipped when serializing the results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    answer: Option<String>,
+}
+
+impl From<Option<BenchmarkResults>> for WorkerOutput {
+    fn from(result: Option<BenchmarkResults>) -> Self {
+        let samples_ns = result
+            .iter()
+            .flat_map(|r| &r.samples)
+            .map(|s| s.as_nanos() as u64)
+            .collect();
+        let answer = result.as_ref().and_then(|r| r.answer.clone());
+        Self {
+            result,
+            samples_ns,
+            answer,
+        }
+    }
+}
+
+impl From<WorkerOutput> for Option<BenchmarkResults> {
+    fn from(output: WorkerOutput) -> Self {
+        output.result.map(|result| BenchmarkResults {
+            samples: output
+                .samples_ns
+                .into_iter()
+                .map(Duration::from_nanos)
+                .collect(),
+            answer: output.answer,
+            ..result
+        })
+    }
+}
+
+/// Benchmark every part of the given days, each in a fresh child process, so nothing left behind
+/// by one solver (allocator state, caches, lazy statics) affects the next
+pub fn run_isolated_benchmarks(
+    solver_map: &SolverMap,
+    days: impl Iterator<Item = Day>,
+    policy: &BenchmarkPolicy,
+    options: &IsolationOptions,
+) -> anyhow::Result<BenchmarkMap> {
+    let exe = current_exe()?;
+    let mut all_results = HashMap::new();
+
+    for day in days {
+        let mut part_bench: BenchmarkMap = HashMap::new();
+        for part in 1..=2 as Part {
+            if !solver_map.contains_key(&(day, part)) {
+                continue;
+            }
+
+            progress::start_part(day, part);
+            match run_worker(&exe, day, part, policy, options) {
+                Ok(Some(result)) => {
+                    part_bench.insert((day, part), result);
+                }
+                Ok(None) => log::debug!("day {day} part {part} not solved"),
+                // Ctrl-C reaches the worker too, which just exits
+                Err(_) if progress::interrupted() => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        all_results.extend(part_bench.clone());
+        log_day_results(day, &part_bench);
+        if progress::interrupted() {
+            log::warn!("interrupted, skipping the remaining days");
+            break;
+        }
+    }
+
+    Ok(all_results)
+}
+
+fn run_worker(
+    exe: &Path,
+    day: Day,
+    part: Part,
+    policy: &BenchmarkPolicy,
+    options: &IsolationOptions,
+) -> anyhow::Result<Option<BenchmarkResults>> {
+    let mut command = Command::new(exe);
+    command
+        .args([WORKER_COMMAND, &day.to_string(), &part.to_string()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit());
+    if let Some(cpu) = options.pin_cpu {
+        command.args(["--pin-cpu", &cpu.to_string()]);
+    }
+
+    let mut child = command
+        .spawn()
+        .context("failed to start the benchmark worker")?;
+    // dropping stdin closes it, so the worker knows the policy is complete
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(toml::to_string(policy)?.as_bytes())?;
+    let output = child.wait_with_output()?;
+    anyhow::ensure!(
+        output.status.success(),
+        "the benchmark worker for day {day} part {part} failed ({})",
+        output.status
+    );
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let (_, results) = stdout
+        .rsplit_once(RESULTS_MARKER)
+        .context("the benchmark worker didn't send any results")?;
+    let output: WorkerOutput = toml::from_str(results)?;
+    Ok(output.into())
+}
+
+/// The child process side of [`run_isolated_benchmarks`]: reads the policy from stdin, benchmarks
+/// one part and writes the results to stdout
+pub fn run_worker_process(
+    solver_map: &SolverMap,
+    puzzle_source: &impl PuzzleSource,
+    day: Day,
+    part: Part,
+    pin_cpu: Option<usize>,
+) -> anyhow::Result<()> {
+    if let Some(cpu) = pin_cpu {
+        pin_to_cpu(cpu)?;
+    }
+
+    let policy: BenchmarkPolicy = toml::from_str(&io::read_to_string(io::stdin())?)?;
+    let solver = solver_map
+        .get(&(day, part))
+        .ok_or(RunError::NoSolver { day, part })?;
+    let input = puzzle_source.get_input(day)?;
+
+    let output = WorkerOutput::from(benchmark_solver(day, solver.as_ref(), &input, &policy).ok());
+    println!("\n{RESULTS_MARKER}\n{}", toml::to_string(&output)?);
+    Ok(())
+}
+
+/// Pin the current thread, and any threads it starts afterward, to a single CPU core
+#[cfg(target_os = "linux")]
+fn pin_to_cpu(cpu: usize) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        cpu < libc::CPU_SETSIZE as usize,
+        "CPU {cpu} is out of range"
+    );
+    let set = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(cpu, &mut set);
+        set
+    };
+    if unsafe { libc::sched_setaffinity(0, size_of::<libc::cpu_set_t>(), &set) } != 0 {
+        return Err(io::Error::last_os_error()).context(format!("failed to pin to CPU {cpu}"));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_to_cpu(_cpu: usize) -> anyhow::Result<()> {
+    anyhow::bail!("pinning to a CPU is only supported on Linux")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc::PuzzleInput;
+
+    #[test]
+    fn test_worker_output_keeps_samples() {
+        let solver = |_: &PuzzleInput| Some("2".to_string());
+        let input = PuzzleInput::from("");
+        let result = benchmark_solver(1, &solver, &input, &BenchmarkPolicy::quick()).unwrap();
+        let serialized = toml::to_string(&WorkerOutput::from(Some(result.clone()))).unwrap();
+
+        // anything the solver printed before the results is ignored
+        let stdout = format!("debug output\n{RESULTS_MARKER}\n{serialized}");
+        let (_, results) = stdout.rsplit_once(RESULTS_MARKER).unwrap();
+        let parsed: Option<BenchmarkResults> =
+            toml::from_str::<WorkerOutput>(results).unwrap().into();
+
+        let parsed = parsed.unwrap();
+        assert_eq!(parsed.iterations, result.iterations);
+        assert_eq!(parsed.samples, result.samples);
+        assert_eq!(parsed.peak_stack, result.peak_stack);
+        assert_eq!(parsed.answer.as_deref(), Some("2"));
+        assert_eq!(parsed.input_hash, result.input_hash);
+    }
+}
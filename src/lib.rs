@@ -0,0 +1,266 @@
+//! Solutions for Advent of Code 2025, plus the tooling to fetch inputs and benchmark them.
+//!
+//! The `aoc-2025` binary is a thin CLI over this library. Other tools can use it directly:
+//!
+//! ```
+//! // every (day, part) that has a solver
+//! let solvers = aoc_2025::list_solvers();
+//! assert!(solvers.contains(&(1, 1)));
+//!
+//! // solve a part with any input
+//! let answer = aoc_2025::solve(1, 1, "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82").unwrap();
+//! assert_eq!(answer, "3");
+//! ```
+//!
+//! [`run_benchmark`] returns the same [`BenchmarkResults`] that are cached in `.benchmark_cache.toml`.
+//!
+//! The benchmark measures memory usage and counts allocations with [`CountingAlloc`], which only
+//! works if it's installed as the global allocator, as the binary does. Linking the library leaves
+//! the allocator alone.
+
+mod alloc;
+mod answers;
+mod aoc;
+mod bench;
+mod benchmark_cache;
+mod benchmark_history;
+mod chart;
+mod cli;
+mod compare;
+mod config;
+mod cpu_time;
+mod fingerprint;
+mod heap_profile;
+mod html;
+mod inputs;
+mod isolate;
+mod profile;
+mod progress;
+mod readme;
+mod report;
+mod scaling;
+mod solutions;
+mod stack;
+mod stars;
+mod threads;
+mod utils;
+// the frames and shapes solvers can draw, not all of which are used yet
+#[allow(dead_code)]
+mod visualize;
+
+use crate::aoc::{PuzzleInput, PuzzleSource, SolverFn, SolverMap, get_days_iter};
+use crate::bench::{BenchmarkError, benchmark_with_policy};
+use crate::inputs::CachedOnlinePuzzleSource;
+use crate::solutions::get_solvers;
+use itertools::Itertools;
+use std::cell::LazyCell;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+pub use crate::alloc::CountingAlloc;
+pub use crate::aoc::{Day, Part};
+pub use crate::bench::{BenchmarkPolicy, BenchmarkResults};
+pub use crate::fingerprint::Fingerprint;
+
+// the tests measure memory too
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc::new();
+
+pub(crate) type BenchmarkMap = HashMap<(Day, Part), BenchmarkResults>;
+
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum RunError {
+    #[error("there is no solver for day {day} part {part}")]
+    NoSolver { day: Day, part: Part },
+    #[error("day {day} part {part} is not solved")]
+    NotSolved { day: Day, part: Part },
+    #[error("the benchmark of day {day} part {part} was interrupted")]
+    Interrupted { day: Day, part: Part },
+}
+
+/// Every (day, part) that has a solver, in order
+pub fn list_solvers() -> Vec<(Day, Part)> {
+    get_solvers().into_keys().sorted().collect()
+}
+
+/// Run the solver for a day and part on the given input, returning the answer
+pub fn solve(day: Day, part: Part, input: &str) -> Result<String, RunError> {
+    let solvers = get_solvers();
+    let solver = solvers
+        .get(&(day, part))
+        .ok_or(RunError::NoSolver { day, part })?;
+
+    solver(&PuzzleInput::from(input)).ok_or(RunError::NotSolved { day, part })
+}
+
+/// A solver together with its input, to run it repeatedly without looking it up every time, e.g.
+/// from another benchmark harness
+pub struct PreparedSolver {
+    solver: SolverFn,
+    input: PuzzleInput,
+}
+
+impl PreparedSolver {
+    pub fn run(&self) -> Option<String> {
+        (self.solver)(&self.input)
+    }
+}
+
+/// Look up the solver for a day and part, to run it on the given input with [`PreparedSolver::run`]
+pub fn prepare_solver(day: Day, part: Part, input: &str) -> Result<PreparedSolver, RunError> {
+    let solver = get_solvers()
+        .remove(&(day, part))
+        .ok_or(RunError::NoSolver { day, part })?;
+
+    Ok(PreparedSolver {
+        solver,
+        input: PuzzleInput::from(input),
+    })
+}
+
+/// The puzzle input of a day, from the cache or downloaded with the token in the config
+pub fn get_puzzle_input(day: Day) -> anyhow::Result<String> {
+    let input = CachedOnlinePuzzleSource::new()?.get_input(day)?;
+    Ok(input.get_raw().to_string())
+}
+
+/// Benchmark the solver for a day and part on the given input
+pub fn run_benchmark(day: Day, part: Part, input: &str) -> Result<BenchmarkResults, RunError> {
+    run_benchmark_with_policy(day, part, input, &BenchmarkPolicy::default())
+}
+
+/// Benchmark the solver for a day and part on the given input, with a custom time budget
+pub fn run_benchmark_with_policy(
+    day: Day,
+    part: Part,
+    input: &str,
+    policy: &BenchmarkPolicy,
+) -> Result<BenchmarkResults, RunError> {
+    let solvers = get_solvers();
+    let solver = solvers
+        .get(&(day, part))
+        .ok_or(RunError::NoSolver { day, part })?;
+    let input = PuzzleInput::from(input);
+
+    benchmark_solver(day, solver.as_ref(), &input, policy).map_err(|e| match e {
+        BenchmarkError::NotImplemented => RunError::NotSolved { day, part },
+        BenchmarkError::Interrupted => RunError::Interrupted { day, part },
+    })
+}
+
+/// Entry point of the `aoc-2025` binary, which is the only user of the rest of the crate
+#[doc(hidden)]
+pub fn cli_main() -> Result<(), String> {
+    cli::main()
+}
+
+/// The last day that has a solver for either part
+pub(crate) fn get_last_day(solver_map: &SolverMap) -> Option<Day> {
+    get_days_iter()
+        .flat_map(|day| {
+            solver_map
+                .get(&(day, 1))
+                .or(solver_map.get(&(day, 2)))
+                .and(Some(day))
+        })
+        .last()
+}
+
+/// Benchmark every part of the given days, logging the results as they're measured. After a Ctrl-C
+/// (see [`progress::install_interrupt_handler`]), only the parts that finished are returned.
+pub(crate) fn run_benchmarks(
+    solver_map: &SolverMap,
+    puzzle_source: &impl PuzzleSource,
+    days: impl Iterator<Item = Day>,
+    policy: &BenchmarkPolicy,
+) -> BenchmarkMap {
+    let mut all_results = HashMap::new();
+
+    for day in days {
+        let mut part_bench: BenchmarkMap = HashMap::new();
+        let input = LazyCell::new(|| {
+            puzzle_source
+                .get_input(day)
+                .expect("failed to get puzzle input")
+        });
+        for part in 1..=2 as Part {
+            if let Some(solver) = solver_map.get(&(day, part)) {
+                progress::start_part(day, part);
+                match benchmark_solver(day, solver.as_ref(), &input, policy) {
+                    Ok(result) => {
+                        part_bench.insert((day, part), result);
+                    }
+                    Err(BenchmarkError::Interrupted) => break,
+                    Err(_) => log::debug!("day {day} part {part} not solved"),
+                }
+            }
+        }
+
+        all_results.extend(part_bench.clone());
+        log_day_results(day, &part_bench);
+        if progress::interrupted() {
+            log::warn!("interrupted, skipping the remaining days");
+            break;
+        }
+    }
+
+    all_results
+}
+
+/// Benchmark a solver, keeping the answer from its first run and what the result was measured with
+pub(crate) fn benchmark_solver(
+    day: Day,
+    solver: &(dyn Fn(&PuzzleInput) -> Option<String> + Sync),
+    input: &PuzzleInput,
+    policy: &BenchmarkPolicy,
+) -> Result<BenchmarkResults, BenchmarkError> {
+    let answer = OnceLock::new();
+    let mut result = benchmark_with_policy(
+        || {
+            let result = solver(input);
+            if answer.get().is_none() {
+                let _ = answer.set(result.clone());
+            }
+            result
+        },
+        policy,
+    )?;
+
+    result.answer = answer.into_inner().flatten();
+    result.input_hash = Some(input.hash());
+    result.fingerprint = Some(Fingerprint::current(day));
+    Ok(result)
+}
+
+/// Log the results of one day, if any of its parts were benchmarked
+pub(crate) fn log_day_results(day: Day, results: &BenchmarkMap) {
+    if results.is_empty() {
+        return;
+    }
+
+    log::info!(
+        "Day {day}: \n - part 1: {}\n - part 2: {} ",
+        results
+            .get(&(day, 1))
+            .map(|t| t.to_string())
+            .unwrap_or("-".to_string()),
+        results
+            .get(&(day, 2))
+            .map(|t| t.to_string())
+            .unwrap_or("-".to_string()),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_missing_day() {
+        assert_eq!(
+            solve(25, 1, "").unwrap_err(),
+            RunError::NoSolver { day: 25, part: 1 }
+        );
+    }
+}
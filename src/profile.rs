@@ -0,0 +1,147 @@
+use crate::RunError;
+use crate::aoc::{Day, Part, PuzzleSource, SolverMap};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct ProfileOptions {
+    /// How long to keep running the solver
+    pub duration: Duration,
+    /// Samples per second
+    pub frequency: i32,
+    pub output_dir: PathBuf,
+}
+
+impl Default for ProfileOptions {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(5),
+            frequency: 999,
+            output_dir: PathBuf::from("profiles"),
+        }
+    }
+}
+
+/// What [`profile_solver`] wrote
+#[derive(Debug, Clone)]
+pub struct ProfileOutput {
+    pub runs: usize,
+    pub flamegraph: PathBuf,
+    pub pprof: PathBuf,
+}
+
+/// Run a solver in a loop under a sampling profiler, at least once and until the duration is up,
+/// then write a flamegraph SVG and a pprof protobuf to the output directory
+pub fn profile_solver(
+    solver_map: &SolverMap,
+    puzzle_source: &impl PuzzleSource,
+    day: Day,
+    part: Part,
+    options: &ProfileOptions,
+) -> anyhow::Result<ProfileOutput> {
+    let solver = solver_map
+        .get(&(day, part))
+        .ok_or(RunError::NoSolver { day, part })?;
+    let input = puzzle_source.get_input(day)?;
+    // don't spend the whole duration profiling a part that gives up immediately
+    solver(&input).ok_or(RunError::NotSolved { day, part })?;
+
+    let profiler = Profiler::start(options.frequency)?;
+    let start = Instant::now();
+    let mut runs = 0;
+    while runs == 0 || start.elapsed() < options.duration {
+        std::hint::black_box(solver(std::hint::black_box(&input)));
+        runs += 1;
+    }
+
+    fs::create_dir_all(&options.output_dir)?;
+    let name = format!("day{day:02}-part{part}");
+    let output = ProfileOutput {
+        runs,
+        flamegraph: options.output_dir.join(format!("{name}.svg")),
+        pprof: options.output_dir.join(format!("{name}.pb")),
+    };
+    profiler.finish(&output)?;
+    Ok(output)
+}
+
+#[cfg(target_os = "linux")]
+struct Profiler(pprof::ProfilerGuard<'static>);
+
+#[cfg(target_os = "linux")]
+impl Profiler {
+    fn start(frequency: i32) -> anyhow::Result<Self> {
+        Ok(Self(
+            pprof::ProfilerGuardBuilder::default()
+                .frequency(frequency)
+                // unwinding through these can crash
+                .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+                .build()?,
+        ))
+    }
+
+    fn finish(self, output: &ProfileOutput) -> anyhow::Result<()> {
+        use pprof::protos::Message;
+
+        let report = self.0.report().build()?;
+        report.flamegraph(fs::File::create(&output.flamegraph)?)?;
+
+        let mut content = Vec::new();
+        report.pprof()?.write_to_vec(&mut content)?;
+        fs::write(&output.pprof, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+struct Profiler;
+
+#[cfg(not(target_os = "linux"))]
+impl Profiler {
+    fn start(_frequency: i32) -> anyhow::Result<Self> {
+        anyhow::bail!("profiling is only supported on Linux")
+    }
+
+    fn finish(self, _output: &ProfileOutput) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc::{FixedDataSource, PuzzleInput};
+    use std::collections::HashMap;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_profile_solver() {
+        let mut solver_map: SolverMap = HashMap::new();
+        solver_map.insert(
+            (1, 1),
+            Box::new(|input: &PuzzleInput| Some(input.get_raw().repeat(1000).len().to_string())),
+        );
+        let puzzle_source = FixedDataSource {
+            lines: vec!["abc".to_string()],
+        };
+        let output_dir = std::env::temp_dir().join(format!("aoc-profile-{}", std::process::id()));
+        let options = ProfileOptions {
+            duration: Duration::from_millis(200),
+            output_dir: output_dir.clone(),
+            ..ProfileOptions::default()
+        };
+
+        let output = profile_solver(&solver_map, &puzzle_source, 1, 1, &options).unwrap();
+        assert!(output.runs > 1);
+        assert!(
+            fs::read_to_string(&output.flamegraph)
+                .unwrap()
+                .contains("<svg")
+        );
+        assert!(fs::metadata(&output.pprof).unwrap().len() > 0);
+        assert!(profile_solver(&solver_map, &puzzle_source, 1, 2, &options).is_err());
+
+        fs::remove_dir_all(output_dir).unwrap();
+    }
+}
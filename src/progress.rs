@@ -0,0 +1,246 @@
+use crate::aoc::{Day, Part};
+use crate::bench::{format_count, format_duration};
+use std::io::{IsTerminal, Write, stderr};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+/// Clears the status line, so log messages don't end up on it
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static PROGRESS: Progress = Progress::new();
+/// Held by the display while it draws, and by the benchmark while it measures memory. The
+/// allocation counters are shared by every thread, and drawing allocates.
+static DRAWING: Mutex<()> = Mutex::new(());
+
+/// Set by the first Ctrl-C, the benchmarks stop at the next iteration when it is
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// On the first Ctrl-C, let the benchmark stop at the next iteration and save what it finished.
+/// The second one quits right away, for solvers that take too long to wait for.
+pub fn install_interrupt_handler() -> anyhow::Result<()> {
+    ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            eprintln!("{CLEAR_LINE}interrupted again, quitting");
+            std::process::exit(130);
+        }
+        log::warn!("stopping after the current iteration, press Ctrl-C again to quit right away");
+    })?;
+    Ok(())
+}
+
+/// What the benchmark is doing, updated by the benchmark thread and read by the display
+struct Progress {
+    /// Timed iterations of the current part so far
+    iterations: AtomicUsize,
+    /// Timed iterations the current part will run, 0 until that's known
+    target: AtomicUsize,
+    parts_started: AtomicUsize,
+    current: Mutex<Option<CurrentPart>>,
+}
+
+struct CurrentPart {
+    day: Day,
+    part: Part,
+    /// How many parts were benchmarked before this one
+    parts_done: usize,
+    started: Instant,
+    /// When the timed iterations started
+    timing_started: Option<Instant>,
+}
+
+impl Progress {
+    const fn new() -> Self {
+        Self {
+            iterations: AtomicUsize::new(0),
+            target: AtomicUsize::new(0),
+            parts_started: AtomicUsize::new(0),
+            current: Mutex::new(None),
+        }
+    }
+}
+
+/// Called before benchmarking each part
+pub(crate) fn start_part(day: Day, part: Part) {
+    PROGRESS.iterations.store(0, Ordering::Relaxed);
+    PROGRESS.target.store(0, Ordering::Relaxed);
+    *PROGRESS.current.lock().unwrap() = Some(CurrentPart {
+        day,
+        part,
+        parts_done: PROGRESS.parts_started.fetch_add(1, Ordering::Relaxed),
+        started: Instant::now(),
+        timing_started: None,
+    });
+}
+
+/// Called once the number of timed iterations is known
+pub(crate) fn start_timing(target: usize) {
+    PROGRESS.iterations.store(0, Ordering::Relaxed);
+    PROGRESS.target.store(target, Ordering::Relaxed);
+    if let Some(current) = PROGRESS.current.lock().unwrap().as_mut() {
+        current.timing_started = Some(Instant::now());
+    }
+}
+
+/// Stops the status line from being drawn until the guard is dropped, so its allocations aren't
+/// counted as the solver's
+pub(crate) fn pause_display() -> MutexGuard<'static, ()> {
+    DRAWING.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Cheap enough to call after every sample
+pub(crate) fn add_iterations(count: usize) {
+    PROGRESS.iterations.fetch_add(count, Ordering::Relaxed);
+}
+
+/// Redraws a status line on stderr with the current part, its iterations, the elapsed time and an
+/// estimate of the time left, until it's dropped. Nothing is drawn if stderr isn't a terminal.
+pub struct ProgressDisplay {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ProgressDisplay {
+    /// `total_parts` is how many parts will be benchmarked, and `part_estimate` how long one is
+    /// expected to take before any of them finished
+    pub fn start(total_parts: usize, part_estimate: Duration) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        PROGRESS.parts_started.store(0, Ordering::Relaxed);
+        let thread = stderr().is_terminal().then(|| {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let start = Instant::now();
+                while !stop.load(Ordering::Relaxed) {
+                    let drawing = DRAWING.lock().unwrap_or_else(PoisonError::into_inner);
+                    let line = {
+                        let current = PROGRESS.current.lock().unwrap();
+                        current.as_ref().map(|current| {
+                            format_status(current, total_parts, start.elapsed(), part_estimate)
+                        })
+                    };
+                    if let Some(line) = line {
+                        let mut stderr = stderr().lock();
+                        let _ = write!(stderr, "{CLEAR_LINE}{line}");
+                        let _ = stderr.flush();
+                    }
+                    drop(drawing);
+                    thread::sleep(REDRAW_INTERVAL);
+                }
+            })
+        });
+
+        Self { stop, thread }
+    }
+}
+
+impl Drop for ProgressDisplay {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+            eprint!("{CLEAR_LINE}");
+        }
+        *PROGRESS.current.lock().unwrap() = None;
+    }
+}
+
+fn format_status(
+    current: &CurrentPart,
+    total_parts: usize,
+    elapsed: Duration,
+    part_estimate: Duration,
+) -> String {
+    let iterations = PROGRESS.iterations.load(Ordering::Relaxed);
+    let target = PROGRESS.target.load(Ordering::Relaxed);
+    let part_elapsed = current.started.elapsed();
+    let parts_done = current.parts_done;
+
+    // once some parts are done, they're a better guess than the policy
+    let part_estimate = if parts_done > 0 {
+        elapsed.saturating_sub(part_elapsed) / parts_done as u32
+    } else {
+        part_estimate
+    };
+    let current_left = match current.timing_started {
+        Some(timing_started) if iterations > 0 => timing_started
+            .elapsed()
+            .mul_f64((target.saturating_sub(iterations)) as f64 / iterations as f64),
+        _ => part_estimate.saturating_sub(part_elapsed),
+    };
+    let parts_left = total_parts.saturating_sub(parts_done + 1) as u32;
+    let eta = current_left + part_estimate * parts_left;
+
+    let iterations = match target {
+        // still warming up, or in a worker process that doesn't report its iterations
+        0 => "measuring".to_string(),
+        _ => format!(
+            "{}/{} iterations",
+            format_count(iterations),
+            format_count(target)
+        ),
+    };
+    format!(
+        "[{}/{total_parts}] day {:02} part {}: {iterations}, {} elapsed, ETA {}",
+        (parts_done + 1).min(total_parts),
+        current.day,
+        current.part,
+        format_duration(elapsed),
+        format_duration(eta),
+    )
+}
+
+/// Wraps another logger to clear the status line before each message, which the display then
+/// draws again below it
+pub struct ProgressLogger<L>(pub L);
+
+impl<L: log::Log> log::Log for ProgressLogger<L> {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            let stderr = stderr();
+            let mut stderr = stderr.lock();
+            if PROGRESS.current.lock().unwrap().is_some() && stderr.is_terminal() {
+                let _ = write!(stderr, "{CLEAR_LINE}");
+            }
+            self.0.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_status() {
+        let current = CurrentPart {
+            day: 8,
+            part: 2,
+            parts_done: 3,
+            started: Instant::now(),
+            timing_started: None,
+        };
+        let status = format_status(
+            &current,
+            10,
+            Duration::from_secs(30),
+            Duration::from_secs(5),
+        );
+        // the finished parts took 10s each, so 7 parts left take 70s
+        assert!(status.starts_with("[4/10] day 08 part 2: "), "{status}");
+        assert!(status.contains("30.0s elapsed"), "{status}");
+        assert!(status.ends_with("ETA 70.0s"), "{status}");
+    }
+}
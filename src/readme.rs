@@ -1,32 +1,136 @@
 use crate::BenchmarkMap;
-use crate::aoc::get_days_iter;
-use crate::bench::{BenchmarkResults, format_duration, format_memory};
+use crate::aoc::{Day, Part, get_days_iter};
+use crate::bench::{BenchmarkResults, format_count, format_duration, format_memory};
+use crate::benchmark_cache::MachineBenchmarks;
+use crate::chart::{memory_chart, time_chart};
+use crate::stars::Stars;
 use crate::utils::get_cpu_name;
 use itertools::Itertools;
+use std::collections::HashMap;
 use std::env::current_dir;
-use std::fs::read_to_string;
-use std::iter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fs, io, iter};
 
-const MARKER_START: &str = "<!---BENCH_START--->";
-const MARKER_END: &str = "<!---BENCH_END--->";
+const MARKER_START: &str = "<!---BENCH_START";
+const MARKER_END: &str = "<!---BENCH_END";
+const MARKER_CLOSE: &str = "--->";
 
-fn format_day_bench(result: Option<&BenchmarkResults>) -> String {
-    if let Some(result) = result {
+/// The blocks that can be placed in the README, with their default templates. `bench` is the block
+/// between the unnamed `<!---BENCH_START--->` and `<!---BENCH_END--->` markers.
+const BLOCKS: [(&str, &str); 6] = [
+    ("bench", "{star_road}\n\n{tables}\n\n{charts}"),
+    (
+        "environment",
+        "Benchmark CPU: **{cpu}**, built with `{rustc}` ({profile} profile)",
+    ),
+    ("stars", "{star_road}"),
+    ("table", "{tables}"),
+    ("totals", "{totals}"),
+    ("charts", "{charts}"),
+];
+
+/// Where the charts are written, relative to the README
+const CHARTS_DIR: &str = "benchmarks";
+
+/// Used when writing to a separate file that doesn't exist yet
+const DEFAULT_BENCHMARKS_FILE: &str = "# Benchmarks
+
+<!---BENCH_START:environment--->
+<!---BENCH_END:environment--->
+
+<!---BENCH_START:stars--->
+<!---BENCH_END:stars--->
+
+<!---BENCH_START:table--->
+<!---BENCH_END:table--->
+
+<!---BENCH_START:totals--->
+<!---BENCH_END:totals--->
+
+<!---BENCH_START:charts--->
+<!---BENCH_END:charts--->
+";
+
+#[derive(Debug, Default, Clone)]
+pub struct ReadmeOptions {
+    /// Show the median, spread and p95 instead of the average duration
+    pub show_statistics: bool,
+    /// Add columns with the number of allocations and the total bytes allocated per run
+    pub show_allocations: bool,
+    /// Show every machine in one table, with a column per CPU, instead of a table per machine
+    pub combined: bool,
+    /// Write to this file instead of README.md. It's created if it doesn't exist.
+    pub output: Option<PathBuf>,
+    /// Templates replacing the defaults, by block name
+    pub templates: HashMap<String, String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReadmeError {
+    #[error("failed to update {path}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+    #[error("{path} has no <!---BENCH_START---> or <!---BENCH_START:name---> markers")]
+    MissingMarkers { path: PathBuf },
+    #[error("the {name} block is missing its {end} marker")]
+    UnclosedBlock { name: String, end: String },
+    #[error("unknown README block {name}, expected one of {expected}")]
+    UnknownBlock { name: String, expected: String },
+}
+
+fn format_day_bench(
+    result: Option<&BenchmarkResults>,
+    stars: &Stars,
+    day: Day,
+    part: Part,
+    options: &ReadmeOptions,
+) -> String {
+    match result {
+        Some(result) => {
+            let duration = if options.show_statistics {
+                format!(
+                    "{} ±{} (p95 {})",
+                    format_duration(result.median_duration),
+                    format_duration(result.std_dev),
+                    format_duration(result.p95_duration)
+                )
+            } else {
+                format_duration(result.average_duration)
+            };
+            let mut bench = duration;
+            // results cached before CPU time was measured don't have it
+            if !result.cpu_time().is_zero() {
+                bench.push_str(&format!(" / {} CPU", format_duration(result.cpu_time())));
+            }
+            bench.push_str(&format!(" / {}", format_memory(result.peak_memory)));
+            if stars.lacks_star(day, part) {
+                bench.push_str(" (no star)");
+            }
+            if result.stale {
+                bench.push_str(" (stale)");
+            }
+            bench
+        }
+        None if stars.is_unsolved(day, part) => "unsolved".to_owned(),
+        None => "-".to_owned(),
+    }
+}
+
+fn format_day_allocations(result: Option<&BenchmarkResults>) -> String {
+    result.map_or("-".to_owned(), |result| {
         format!(
             "{} / {}",
-            format_duration(result.average_duration),
-            format_memory(result.peak_memory)
+            format_count(result.allocations),
+            format_memory(result.allocated_bytes)
         )
-    } else {
-        "-".to_owned()
-    }
+    })
 }
 
 fn pad(s: &str, len: usize) -> String {
     format!("{:<len$}", s, len = len)
 }
 
-fn format_row<const N: usize>(row: &[impl AsRef<str>; N], widths: &[usize; N]) -> String {
+fn format_row(row: &[impl AsRef<str>], widths: &[usize]) -> String {
     let inner = row
         .iter()
         .zip_eq(widths)
@@ -35,67 +139,436 @@ fn format_row<const N: usize>(row: &[impl AsRef<str>; N], widths: &[usize; N]) -
     format!("| {inner} |")
 }
 
-fn format_table<const N: usize>(
-    headers: &[impl AsRef<str>; N],
-    lines: &[[impl AsRef<str>; N]],
+pub fn format_table<S: AsRef<str>>(
+    headers: &[impl AsRef<str>],
+    lines: &[impl AsRef<[S]>],
 ) -> String {
-    let column_widths: [usize; N] = (0..N)
+    let column_widths = (0..headers.len())
         .map(|i| {
             iter::once(headers[i].as_ref())
-                .chain(lines.iter().map(|l| l[i].as_ref()))
+                .chain(lines.iter().map(|l| l.as_ref()[i].as_ref()))
                 .map(|s| s.chars().count())
                 .max()
                 .unwrap()
         })
-        .collect_vec()
-        .try_into()
-        .unwrap();
+        .collect_vec();
     iter::once(format_row(headers, &column_widths))
         .chain(iter::once(format!(
             "|{}|",
-            column_widths.map(|n| "-".repeat(n + 2)).join("|")
+            column_widths.iter().map(|n| "-".repeat(n + 2)).join("|")
         )))
-        .chain(lines.iter().map(|line| format_row(line, &column_widths)))
+        .chain(
+            lines
+                .iter()
+                .map(|line| format_row(line.as_ref(), &column_widths)),
+        )
         .join("\n")
 }
 
-pub fn update_readme(results: &BenchmarkMap) {
-    let readme_path = current_dir().unwrap().join("README.md");
-    let contents = read_to_string(&readme_path).unwrap();
-    let (start, middle) = contents.split_once(MARKER_START).unwrap();
-    let (_, footer) = middle.split_once(MARKER_END).unwrap();
+fn format_day_link(day: Day, has_results: bool) -> String {
+    if has_results {
+        format!("[{day:02}](src/solutions/day{day:02}.rs)")
+    } else {
+        format!("{day:02}")
+    }
+}
+
+fn sum_durations<'a>(results: impl Iterator<Item = &'a BenchmarkResults>) -> Duration {
+    results.map(|result| result.average_duration).sum()
+}
+
+/// One row per day, with a column per part, and a row with the total time of each part
+fn format_machine_table(results: &BenchmarkMap, stars: &Stars, options: &ReadmeOptions) -> String {
+    let part_total = |part: Part| {
+        format!(
+            "**{}**",
+            format_duration(sum_durations(
+                results
+                    .iter()
+                    .filter(|((_, p), _)| *p == part)
+                    .map(|(_, r)| r)
+            ))
+        )
+    };
+    let allocations_total = |part: Part| {
+        let allocations = results
+            .iter()
+            .filter(|((_, p), _)| *p == part)
+            .map(|(_, r)| r.allocations)
+            .sum();
+        format!("**{}**", format_count(allocations))
+    };
 
-    let days = get_days_iter()
+    let rows = get_days_iter()
         .map(|d| {
             let r1 = results.get(&(d, 1));
             let r2 = results.get(&(d, 2));
+            let mut row = vec![
+                format_day_link(d, r1.or(r2).is_some()),
+                format_day_bench(r1, stars, d, 1, options),
+            ];
+            if options.show_allocations {
+                row.push(format_day_allocations(r1));
+            }
+            row.push(format_day_bench(r2, stars, d, 2, options));
+            if options.show_allocations {
+                row.push(format_day_allocations(r2));
+            }
+            row
+        })
+        .chain(iter::once({
+            let mut row = vec!["**Total**".to_string(), part_total(1)];
+            if options.show_allocations {
+                row.push(allocations_total(1));
+            }
+            row.push(part_total(2));
+            if options.show_allocations {
+                row.push(allocations_total(2));
+            }
+            row
+        }))
+        .collect_vec();
+
+    if options.show_allocations {
+        format_table(
+            &["Day", "Part 1", "Allocations", "Part 2", "Allocations"],
+            &rows,
+        )
+    } else {
+        format_table(&["Day", "Part 1", "Part 2"], &rows)
+    }
+}
+
+/// One row per day and part, with a column per machine, and a row with the total time of each
+fn format_combined_table(
+    machines: &[MachineBenchmarks],
+    stars: &Stars,
+    options: &ReadmeOptions,
+) -> String {
+    let headers = ["Day", "Part"]
+        .into_iter()
+        .map(str::to_string)
+        .chain(machines.iter().map(|m| m.cpu_name.clone()))
+        .collect_vec();
+    let rows = get_days_iter()
+        .flat_map(|d| [(d, 1), (d, 2)])
+        .map(|(d, part)| {
+            let has_results = machines
+                .iter()
+                .any(|m| m.benchmarks.contains_key(&(d, 1)) || m.benchmarks.contains_key(&(d, 2)));
+            [format_day_link(d, has_results), part.to_string()]
+                .into_iter()
+                .chain(machines.iter().map(|m| {
+                    format_day_bench(m.benchmarks.get(&(d, part)), stars, d, part, options)
+                }))
+                .collect_vec()
+        })
+        .chain(iter::once(
+            ["**Total**".to_string(), String::new()]
+                .into_iter()
+                .chain(machines.iter().map(|m| {
+                    format!(
+                        "**{}**",
+                        format_duration(sum_durations(m.benchmarks.values()))
+                    )
+                }))
+                .collect_vec(),
+        ))
+        .collect_vec();
+
+    format_table(&headers, &rows)
+}
+
+/// One row per machine, summing up all of its results
+fn format_totals(machines: &[MachineBenchmarks]) -> String {
+    let rows = machines
+        .iter()
+        .map(|m| {
             [
-                r1.or(r2).map_or_else(
-                    || format!("{d:02}"),
-                    |_| format!("[{d:02}](src/solutions/day{d:02}.rs)"),
+                m.cpu_name.clone(),
+                m.benchmarks.len().to_string(),
+                format_duration(sum_durations(m.benchmarks.values())),
+                format_memory(
+                    m.benchmarks
+                        .values()
+                        .map(|r| r.peak_memory)
+                        .max()
+                        .unwrap_or_default(),
                 ),
-                format_day_bench(r1),
-                format_day_bench(r2),
+                format_count(m.benchmarks.values().map(|r| r.allocations).sum()),
             ]
         })
         .collect_vec();
 
-    let table_entries = format_table(&["Day", "Part 1", "Part 2"], &days);
+    format_table(
+        &["CPU", "Parts", "Total time", "Peak memory", "Allocations"],
+        &rows,
+    )
+}
 
-    let star_road = get_star_road(results.len(), get_days_iter().count() * 2);
+fn format_tables(machines: &[MachineBenchmarks], stars: &Stars, options: &ReadmeOptions) -> String {
+    if options.combined {
+        format_combined_table(machines, stars, options)
+    } else {
+        machines
+            .iter()
+            .map(|m| {
+                format!(
+                    "Benchmark CPU: **{}**\n\n{}",
+                    m.cpu_name,
+                    format_machine_table(&m.benchmarks, stars, options)
+                )
+            })
+            .join("\n\n")
+    }
+}
 
-    let sys_info = format!("Benchmark CPU: **{}**", get_cpu_name());
-    let bench = format!("{sys_info}\n\n{star_road}\n\n{table_entries}");
+/// Write the time and memory charts of the first machine next to the README, and return the links
+/// to them
+fn write_charts(readme_path: &Path, machine: &MachineBenchmarks) -> io::Result<String> {
+    let dir = readme_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(CHARTS_DIR);
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        dir.join("time.svg"),
+        time_chart(&machine.benchmarks, &machine.cpu_name),
+    )?;
+    fs::write(
+        dir.join("memory.svg"),
+        memory_chart(&machine.benchmarks, &machine.cpu_name),
+    )?;
 
-    let updated_content = format!("{start}{MARKER_START}\n\n{bench}\n\n{MARKER_END}{footer}");
+    Ok(format!(
+        "![Time per part]({CHARTS_DIR}/time.svg)\n\n![Peak memory per part]({CHARTS_DIR}/memory.svg)"
+    ))
+}
 
-    std::fs::write(&readme_path, &updated_content).unwrap();
+/// Replace every `{name}` in the template with its value
+fn render_template(template: &str, values: &[(&str, String)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |rendered, (name, value)| {
+            rendered.replace(&format!("{{{name}}}"), value)
+        })
 }
 
-fn get_star_road(current_stars: usize, total_stars: usize) -> String {
-    format!(
-        "`|{}{}| {current_stars}/{total_stars} stars`",
-        "#".repeat(current_stars),
-        "-".repeat(total_stars - current_stars)
-    )
+/// Replace the contents of every marker block with its rendered template
+fn replace_blocks(
+    contents: &str,
+    rendered: &HashMap<&str, String>,
+    path: &Path,
+) -> Result<String, ReadmeError> {
+    let mut output = String::new();
+    let mut rest = contents;
+    let mut found = false;
+
+    while let Some((start, marker)) =
+        marker_lines(rest).find(|(_, marker)| marker.starts_with(MARKER_START))
+    {
+        // either empty for the unnamed markers, or ":name"
+        let suffix = &marker[MARKER_START.len()..marker.len() - MARKER_CLOSE.len()];
+        let name = suffix.strip_prefix(':').unwrap_or("bench");
+        let content = rendered
+            .get(name)
+            .ok_or_else(|| ReadmeError::UnknownBlock {
+                name: name.to_string(),
+                expected: BLOCKS.iter().map(|(name, _)| name).join(", "),
+            })?;
+
+        let end_marker = format!("{MARKER_END}{suffix}{MARKER_CLOSE}");
+        let after_start = &rest[start + marker.len()..];
+        let (end, _) = marker_lines(after_start)
+            .find(|(_, marker)| *marker == end_marker)
+            .ok_or_else(|| ReadmeError::UnclosedBlock {
+                name: name.to_string(),
+                end: end_marker.clone(),
+            })?;
+
+        output.push_str(&rest[..start]);
+        output.push_str(&format!("{marker}\n\n{content}\n\n{end_marker}"));
+        rest = &after_start[end + end_marker.len()..];
+        found = true;
+    }
+
+    if !found {
+        return Err(ReadmeError::MissingMarkers {
+            path: path.to_path_buf(),
+        });
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// The offset and text of every marker that's on a line of its own. Markers anywhere else, like
+/// the ones quoted in the README's own docs, are left alone.
+fn marker_lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let line_start = *offset;
+            *offset += line.len();
+            Some((line_start, line))
+        })
+        .filter_map(|(line_start, line)| {
+            let marker = line.trim();
+            (marker.starts_with("<!---") && marker.ends_with(MARKER_CLOSE))
+                .then(|| (line_start + line.len() - line.trim_start().len(), marker))
+        })
+}
+
+pub fn update_readme(
+    machines: &[MachineBenchmarks],
+    stars: &Stars,
+    options: &ReadmeOptions,
+) -> Result<(), ReadmeError> {
+    let path = options
+        .output
+        .clone()
+        .unwrap_or_else(|| current_dir().unwrap().join("README.md"));
+    let io_error = |source| ReadmeError::Io {
+        path: path.clone(),
+        source,
+    };
+    let contents = match fs::read_to_string(&path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound && options.output.is_some() => {
+            DEFAULT_BENCHMARKS_FILE.to_string()
+        }
+        contents => contents.map_err(io_error)?,
+    };
+
+    let machines = if machines.is_empty() {
+        vec![MachineBenchmarks {
+            cpu_name: get_cpu_name(),
+            benchmarks: BenchmarkMap::new(),
+        }]
+    } else {
+        machines.to_vec()
+    };
+    let templates = BLOCKS.map(|(name, default)| {
+        (
+            name,
+            options.templates.get(name).map_or(default, String::as_str),
+        )
+    });
+    let values = [
+        ("cpu", machines.iter().map(|m| &m.cpu_name).join(", ")),
+        ("rustc", env!("AOC_RUSTC_VERSION").to_string()),
+        ("profile", env!("AOC_BUILD_PROFILE").to_string()),
+        (
+            "stars",
+            stars
+                .count()
+                .map_or("?".to_string(), |count| count.to_string()),
+        ),
+        ("star_road", stars.star_road()),
+        ("tables", format_tables(&machines, stars, options)),
+        ("totals", format_totals(&machines)),
+    ];
+    // the charts are only written if a template shows them
+    let charts = if templates.iter().any(|(_, t)| t.contains("{charts}")) {
+        write_charts(&path, &machines[0]).map_err(io_error)?
+    } else {
+        String::new()
+    };
+    let values = iter::once(("charts", charts)).chain(values).collect_vec();
+    let rendered = templates
+        .iter()
+        .map(|&(name, template)| (name, render_template(template, &values)))
+        .collect();
+
+    let updated_content = replace_blocks(&contents, &rendered, &path)?;
+    fs::write(&path, updated_content).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered() -> HashMap<&'static str, String> {
+        HashMap::from([
+            ("bench", "all".to_string()),
+            ("stars", "**".to_string()),
+            ("table", "| a |".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_replace_blocks() {
+        let path = PathBuf::from("README.md");
+        let contents = "# Title\n<!---BENCH_START:stars--->\nold\n<!---BENCH_END:stars--->\ntext\n\
+            <!---BENCH_START:table--->\nold\n<!---BENCH_END:table--->\nfooter";
+
+        assert_eq!(
+            replace_blocks(contents, &rendered(), &path).unwrap(),
+            "# Title\n<!---BENCH_START:stars--->\n\n**\n\n<!---BENCH_END:stars--->\ntext\n\
+            <!---BENCH_START:table--->\n\n| a |\n\n<!---BENCH_END:table--->\nfooter"
+        );
+
+        // the unnamed markers are still supported
+        assert_eq!(
+            replace_blocks(
+                "<!---BENCH_START--->\nx\n<!---BENCH_END--->",
+                &rendered(),
+                &path
+            )
+            .unwrap(),
+            "<!---BENCH_START--->\n\nall\n\n<!---BENCH_END--->"
+        );
+
+        // markers that aren't on their own line are only text
+        let quoted = "Use `<!---BENCH_START:table--->` and `<!---BENCH_END:table--->`\n\
+            <!---BENCH_START:stars--->\n<!---BENCH_END:stars--->";
+        assert_eq!(
+            replace_blocks(quoted, &rendered(), &path).unwrap(),
+            "Use `<!---BENCH_START:table--->` and `<!---BENCH_END:table--->`\n\
+            <!---BENCH_START:stars--->\n\n**\n\n<!---BENCH_END:stars--->"
+        );
+    }
+
+    #[test]
+    fn test_replace_blocks_in_readme() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("README.md");
+        let contents = fs::read_to_string(&path).unwrap();
+        let updated = replace_blocks(&contents, &rendered(), &path).unwrap();
+
+        // only the results block is replaced, not the markers quoted in the docs
+        let (before, _) = contents.split_once("<!---BENCH_START--->\n").unwrap();
+        let (_, after) = contents.split_once("\n<!---BENCH_END--->").unwrap();
+        assert_eq!(
+            updated,
+            format!("{before}<!---BENCH_START--->\n\nall\n\n<!---BENCH_END--->{after}")
+        );
+    }
+
+    #[test]
+    fn test_replace_blocks_errors() {
+        let path = PathBuf::from("README.md");
+
+        assert!(matches!(
+            replace_blocks("no markers", &rendered(), &path),
+            Err(ReadmeError::MissingMarkers { .. })
+        ));
+        assert!(matches!(
+            replace_blocks("<!---BENCH_START:stars--->", &rendered(), &path),
+            Err(ReadmeError::UnclosedBlock { .. })
+        ));
+        assert!(matches!(
+            replace_blocks(
+                "<!---BENCH_START:nope--->\n<!---BENCH_END:nope--->",
+                &rendered(),
+                &path
+            ),
+            Err(ReadmeError::UnknownBlock { .. })
+        ));
+    }
+
+    #[test]
+    fn test_render_template() {
+        let values = [("cpu", "Zen 4".to_string()), ("stars", "10".to_string())];
+        assert_eq!(
+            render_template("{cpu}: {stars} stars, {unknown}", &values),
+            "Zen 4: 10 stars, {unknown}"
+        );
+    }
 }
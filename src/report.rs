@@ -0,0 +1,404 @@
+use crate::answers::Verification;
+use crate::aoc::{Day, Part};
+use crate::bench::BenchmarkResults;
+use crate::utils::get_cpu_name;
+use serde::Serialize;
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum OutputFormat {
+    /// The usual human-readable output
+    #[default]
+    Table,
+    Json,
+    Csv,
+    Junit,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown output format {0}")]
+pub struct UnknownFormat(String);
+
+impl FromStr for OutputFormat {
+    type Err = UnknownFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "junit" => Ok(Self::Junit),
+            _ => Err(UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// Everything known about one part after solving, benchmarking or verifying it
+#[derive(Debug, Clone)]
+pub struct PartReport {
+    pub day: Day,
+    pub part: Part,
+    /// `None` if the part isn't solved
+    pub answer: Option<String>,
+    pub input_hash: Option<String>,
+    /// Wall time of a single run, when the part wasn't benchmarked
+    pub time: Option<Duration>,
+    pub benchmark: Option<BenchmarkResults>,
+    pub verification: Option<Verification>,
+}
+
+impl PartReport {
+    pub fn new(day: Day, part: Part, answer: Option<String>) -> Self {
+        Self {
+            day,
+            part,
+            answer,
+            input_hash: None,
+            time: None,
+            benchmark: None,
+            verification: None,
+        }
+    }
+
+    pub fn from_benchmark(day: Day, part: Part, result: &BenchmarkResults) -> Self {
+        Self {
+            input_hash: result.input_hash.clone(),
+            benchmark: Some(result.clone()),
+            ..Self::new(day, part, result.answer.clone())
+        }
+    }
+
+    pub fn status(&self) -> &'static str {
+        match (&self.verification, &self.answer) {
+            (Some(verification), _) => verification.status(),
+            (None, Some(_)) => "solved",
+            (None, None) => "not_solved",
+        }
+    }
+
+    /// The benchmarked average if there is one, otherwise the time of a single run
+    pub fn duration(&self) -> Option<Duration> {
+        self.benchmark
+            .as_ref()
+            .map(|b| b.average_duration)
+            .or(self.time)
+    }
+
+    /// Why this part counts as a failed test case, if it does
+    fn failure(&self) -> Option<String> {
+        match (&self.verification, &self.answer) {
+            (Some(Verification::Mismatch { expected, actual }), _) => Some(format!(
+                "expected {expected}, got {}",
+                actual.as_deref().unwrap_or("no answer")
+            )),
+            (_, None) => Some("not solved".to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// The results of a command, for the machine-readable output formats
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub cpu: String,
+    pub parts: Vec<PartReport>,
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    cpu: &'a str,
+    parts: Vec<JsonPart<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonPart<'a> {
+    day: Day,
+    part: Part,
+    status: &'static str,
+    answer: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_hash: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_ns: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    benchmark: Option<JsonBenchmark>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verification: Option<&'a Verification>,
+}
+
+#[derive(Serialize)]
+struct JsonBenchmark {
+    average_ns: u64,
+    min_ns: u64,
+    median_ns: u64,
+    p95_ns: u64,
+    std_dev_ns: u64,
+    cpu_user_ns: u64,
+    cpu_system_ns: u64,
+    thread_cpu_ns: u64,
+    iterations: usize,
+    sample_count: usize,
+    outliers: usize,
+    peak_memory: usize,
+    peak_stack: usize,
+    allocations: usize,
+    deallocations: usize,
+    allocated_bytes: usize,
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos() as u64
+}
+
+impl From<&BenchmarkResults> for JsonBenchmark {
+    fn from(result: &BenchmarkResults) -> Self {
+        Self {
+            average_ns: nanos(result.average_duration),
+            min_ns: nanos(result.min_duration),
+            median_ns: nanos(result.median_duration),
+            p95_ns: nanos(result.p95_duration),
+            std_dev_ns: nanos(result.std_dev),
+            cpu_user_ns: nanos(result.cpu_user),
+            cpu_system_ns: nanos(result.cpu_system),
+            thread_cpu_ns: nanos(result.thread_cpu),
+            iterations: result.iterations,
+            sample_count: result.sample_count,
+            outliers: result.outliers,
+            peak_memory: result.peak_memory,
+            peak_stack: result.peak_stack,
+            allocations: result.allocations,
+            deallocations: result.deallocations,
+            allocated_bytes: result.allocated_bytes,
+        }
+    }
+}
+
+const CSV_HEADER: [&str; 20] = [
+    "day",
+    "part",
+    "status",
+    "answer",
+    "time_ns",
+    "average_ns",
+    "min_ns",
+    "median_ns",
+    "p95_ns",
+    "std_dev_ns",
+    "cpu_user_ns",
+    "cpu_system_ns",
+    "thread_cpu_ns",
+    "iterations",
+    "peak_memory",
+    "peak_stack",
+    "allocations",
+    "allocated_bytes",
+    "cpu",
+    "input_hash",
+];
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub(crate) fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Report {
+    pub fn new(parts: Vec<PartReport>) -> Self {
+        Self {
+            cpu: get_cpu_name(),
+            parts,
+        }
+    }
+
+    /// Render the report, or `None` for [`OutputFormat::Table`], which each command prints itself
+    pub fn format(&self, format: OutputFormat, name: &str) -> Option<String> {
+        match format {
+            OutputFormat::Table => None,
+            OutputFormat::Json => Some(self.to_json()),
+            OutputFormat::Csv => Some(self.to_csv()),
+            OutputFormat::Junit => Some(self.to_junit(name)),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let report = JsonReport {
+            cpu: &self.cpu,
+            parts: self
+                .parts
+                .iter()
+                .map(|part| JsonPart {
+                    day: part.day,
+                    part: part.part,
+                    status: part.status(),
+                    answer: part.answer.as_deref(),
+                    input_hash: part.input_hash.as_deref(),
+                    time_ns: part.time.map(nanos),
+                    benchmark: part.benchmark.as_ref().map(JsonBenchmark::from),
+                    verification: part.verification.as_ref(),
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&report).unwrap() + "\n"
+    }
+
+    pub fn to_csv(&self) -> String {
+        let rows = self.parts.iter().map(|part| {
+            let bench = part.benchmark.as_ref();
+            let bench_nanos =
+                |f: fn(&BenchmarkResults) -> Duration| bench.map(|b| nanos(f(b)).to_string());
+            let bench_count = |f: fn(&BenchmarkResults) -> usize| bench.map(|b| f(b).to_string());
+
+            [
+                Some(part.day.to_string()),
+                Some(part.part.to_string()),
+                Some(part.status().to_string()),
+                part.answer.clone(),
+                part.time.map(|t| nanos(t).to_string()),
+                bench_nanos(|b| b.average_duration),
+                bench_nanos(|b| b.min_duration),
+                bench_nanos(|b| b.median_duration),
+                bench_nanos(|b| b.p95_duration),
+                bench_nanos(|b| b.std_dev),
+                bench_nanos(|b| b.cpu_user),
+                bench_nanos(|b| b.cpu_system),
+                bench_nanos(|b| b.thread_cpu),
+                bench_count(|b| b.iterations),
+                bench_count(|b| b.peak_memory),
+                bench_count(|b| b.peak_stack),
+                bench_count(|b| b.allocations),
+                bench_count(|b| b.allocated_bytes),
+                Some(self.cpu.clone()),
+                part.input_hash.clone(),
+            ]
+            .map(|field| csv_field(field.as_deref().unwrap_or("")))
+            .join(",")
+        });
+
+        std::iter::once(CSV_HEADER.join(","))
+            .chain(rows)
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    /// Every part is a test case, which fails if it isn't solved or the answer doesn't match
+    pub fn to_junit(&self, name: &str) -> String {
+        let failures = self.parts.iter().filter(|p| p.failure().is_some()).count();
+        let total_time = self
+            .parts
+            .iter()
+            .filter_map(PartReport::duration)
+            .sum::<Duration>();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" time=\"{:.9}\">",
+            xml_escape(name),
+            self.parts.len(),
+            total_time.as_secs_f64()
+        );
+        let _ = writeln!(
+            xml,
+            "  <properties>\n    <property name=\"cpu\" value=\"{}\"/>\n  </properties>",
+            xml_escape(&self.cpu)
+        );
+
+        for part in &self.parts {
+            let _ = write!(
+                xml,
+                "  <testcase classname=\"day{:02}\" name=\"part {}\" time=\"{:.9}\">",
+                part.day,
+                part.part,
+                part.duration().unwrap_or_default().as_secs_f64()
+            );
+            if let Some(failure) = part.failure() {
+                let _ = write!(xml, "\n    <failure message=\"{}\"/>", xml_escape(&failure));
+            }
+            if let Some(answer) = &part.answer {
+                let _ = write!(
+                    xml,
+                    "\n    <system-out>answer: {}</system-out>",
+                    xml_escape(answer)
+                );
+            }
+            xml.push_str("\n  </testcase>\n");
+        }
+
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    fn report() -> Report {
+        Report {
+            cpu: "Test CPU".to_string(),
+            parts: vec![
+                PartReport {
+                    time: Some(Duration::from_micros(5)),
+                    input_hash: Some("abcd".to_string()),
+                    ..PartReport::new(1, 1, Some("1,2".to_string()))
+                },
+                PartReport {
+                    verification: Some(Verification::Mismatch {
+                        expected: "3".to_string(),
+                        actual: Some("4".to_string()),
+                    }),
+                    ..PartReport::new(1, 2, Some("4".to_string()))
+                },
+                PartReport::new(2, 1, None),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_csv_report() {
+        let csv = report().to_csv();
+        let lines = csv.lines().collect_vec();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[1],
+            "1,1,solved,\"1,2\",5000,,,,,,,,,,,,,,Test CPU,abcd"
+        );
+        assert!(lines[2].starts_with("1,2,mismatch,4,"));
+        assert!(lines[3].starts_with("2,1,not_solved,,"));
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let junit = report().to_junit("solve");
+
+        assert!(junit.contains("tests=\"3\" failures=\"2\""));
+        assert!(junit.contains("<failure message=\"expected 3, got 4\"/>"));
+        assert!(junit.contains("<failure message=\"not solved\"/>"));
+        assert!(junit.contains("<system-out>answer: 1,2</system-out>"));
+    }
+
+    #[test]
+    fn test_json_report() {
+        let json: serde_json::Value = serde_json::from_str(&report().to_json()).unwrap();
+
+        assert_eq!(json["cpu"], "Test CPU");
+        assert_eq!(json["parts"][0]["answer"], "1,2");
+        assert_eq!(json["parts"][0]["time_ns"], 5000);
+        assert_eq!(json["parts"][1]["verification"]["status"], "mismatch");
+        assert_eq!(json["parts"][2]["answer"], serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::aoc::{Day, GeneratorMap, Part, SolverMap};
+use crate::bench::{BenchmarkPolicy, BenchmarkResults, format_duration};
+use crate::benchmark_solver;
+use crate::readme::format_table;
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+
+pub const DEFAULT_SIZES: [usize; 5] = [250, 500, 1000, 2000, 4000];
+/// Every size of every run gets the same input
+const SEED: u64 = 2025;
+
+/// The usual complexity classes, to name the one a fitted curve is closest to
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Complexity {
+    Constant,
+    Logarithmic,
+    Linear,
+    Linearithmic,
+    Quadratic,
+    QuadraticLog,
+    Cubic,
+}
+
+impl Complexity {
+    const ALL: [Complexity; 7] = [
+        Self::Constant,
+        Self::Logarithmic,
+        Self::Linear,
+        Self::Linearithmic,
+        Self::Quadratic,
+        Self::QuadraticLog,
+        Self::Cubic,
+    ];
+
+    fn ln_growth(self, n: f64) -> f64 {
+        // clamped so the log factor never goes to zero for tiny sizes
+        let log = n.log2().max(1.0).ln();
+        match self {
+            Self::Constant => 0.0,
+            Self::Logarithmic => log,
+            Self::Linear => n.ln(),
+            Self::Linearithmic => n.ln() + log,
+            Self::Quadratic => 2.0 * n.ln(),
+            Self::QuadraticLog => 2.0 * n.ln() + log,
+            Self::Cubic => 3.0 * n.ln(),
+        }
+    }
+}
+
+impl Display for Complexity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Constant => "O(1)",
+            Self::Logarithmic => "O(log n)",
+            Self::Linear => "O(n)",
+            Self::Linearithmic => "O(n log n)",
+            Self::Quadratic => "O(n²)",
+            Self::QuadraticLog => "O(n² log n)",
+            Self::Cubic => "O(n³)",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The benchmarks of one part at every input size
+#[derive(Debug, Clone)]
+pub struct ScalingResult {
+    pub day: Day,
+    pub part: Part,
+    pub points: Vec<(usize, BenchmarkResults)>,
+}
+
+impl ScalingResult {
+    fn log_points(&self) -> Vec<(f64, f64)> {
+        self.points
+            .iter()
+            .map(|(size, result)| (*size as f64, result.average_duration.as_secs_f64()))
+            .filter(|(size, time)| *size > 0.0 && *time > 0.0)
+            .map(|(size, time)| (size.ln(), time.ln()))
+            .collect()
+    }
+
+    /// The slope of the time against the size on a log-log scale, so `k` for `O(n^k)`
+    pub fn exponent(&self) -> Option<f64> {
+        let points = self.log_points();
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / points.len() as f64;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / points.len() as f64;
+        let covariance = points
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum::<f64>();
+        let variance = points
+            .iter()
+            .map(|(x, _)| (x - mean_x).powi(2))
+            .sum::<f64>();
+
+        (points.len() >= 2 && variance > 0.0).then(|| covariance / variance)
+    }
+
+    /// The complexity class that, scaled by a constant, fits the measurements best
+    pub fn complexity(&self) -> Option<Complexity> {
+        let points = self.log_points();
+        if points.len() < 2 {
+            return None;
+        }
+
+        Complexity::ALL
+            .into_iter()
+            .min_by(|a, b| residual(&points, *a).total_cmp(&residual(&points, *b)))
+    }
+}
+
+/// How far the measurements are from the class, once the best constant factor is picked
+fn residual(points: &[(f64, f64)], complexity: Complexity) -> f64 {
+    let offsets = points
+        .iter()
+        .map(|(ln_size, ln_time)| ln_time - complexity.ln_growth(ln_size.exp()))
+        .collect_vec();
+    let mean = offsets.iter().sum::<f64>() / offsets.len() as f64;
+    offsets.iter().map(|offset| (offset - mean).powi(2)).sum()
+}
+
+/// Benchmark every solved part of the given days on generated inputs of each size. Days without a
+/// generator are skipped.
+pub fn run_scaling(
+    solver_map: &SolverMap,
+    generators: &GeneratorMap,
+    days: impl Iterator<Item = Day>,
+    sizes: &[usize],
+    policy: &BenchmarkPolicy,
+) -> Vec<ScalingResult> {
+    let mut results = Vec::new();
+
+    for day in days {
+        let Some(generator) = generators.get(&day) else {
+            log::debug!("day {day} has no generator");
+            continue;
+        };
+
+        let mut day_results = (1..=2 as Part)
+            .filter(|part| solver_map.contains_key(&(day, *part)))
+            .map(|part| ScalingResult {
+                day,
+                part,
+                points: Vec::new(),
+            })
+            .collect_vec();
+        for &size in sizes {
+            let input = generator(size, &mut fastrand::Rng::with_seed(SEED));
+            for scaling in &mut day_results {
+                let solver = &solver_map[&(day, scaling.part)];
+                match benchmark_solver(day, solver.as_ref(), &input, policy) {
+                    Ok(result) => {
+                        log::info!(
+                            "day {day} part {} with size {size}: {}",
+                            scaling.part,
+                            format_duration(result.average_duration)
+                        );
+                        scaling.points.push((size, result));
+                    }
+                    Err(e) => log::debug!("day {day} part {}: {e}", scaling.part),
+                }
+            }
+        }
+
+        results.extend(day_results.into_iter().filter(|r| !r.points.is_empty()));
+    }
+
+    results
+}
+
+/// A row per part, with the time at every size and the fitted complexity
+pub fn format_scaling(results: &[ScalingResult], sizes: &[usize]) -> String {
+    let headers = ["Day", "Part"]
+        .into_iter()
+        .map(str::to_string)
+        .chain(sizes.iter().map(|size| format!("n = {size}")))
+        .chain(["Exponent", "Closest"].map(str::to_string))
+        .collect_vec();
+    let rows = results
+        .iter()
+        .map(|r| {
+            [r.day.to_string(), r.part.to_string()]
+                .into_iter()
+                .chain(sizes.iter().map(|size| {
+                    r.points
+                        .iter()
+                        .find(|(s, _)| s == size)
+                        .map_or("-".to_string(), |(_, result)| {
+                            format_duration(result.average_duration)
+                        })
+                }))
+                .chain([
+                    r.exponent().map_or("-".to_string(), |e| format!("{e:.2}")),
+                    r.complexity().map_or("-".to_string(), |c| c.to_string()),
+                ])
+                .collect_vec()
+        })
+        .collect_vec();
+
+    format_table(&headers, &rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn scaling(time: impl Fn(f64) -> f64) -> ScalingResult {
+        ScalingResult {
+            day: 1,
+            part: 1,
+            points: DEFAULT_SIZES
+                .iter()
+                .map(|&size| {
+                    let duration = Duration::from_secs_f64(time(size as f64) * 1e-9);
+                    (size, BenchmarkResults::fixture(duration))
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_fit_complexity() {
+        let linear = scaling(|n| 50.0 * n);
+        assert!((linear.exponent().unwrap() - 1.0).abs() < 0.01);
+        assert_eq!(linear.complexity(), Some(Complexity::Linear));
+
+        let quadratic_log = scaling(|n| 3.0 * n * n * n.log2());
+        assert!(quadratic_log.exponent().unwrap() > 2.0);
+        assert_eq!(quadratic_log.complexity(), Some(Complexity::QuadraticLog));
+
+        let constant = scaling(|_| 1000.0);
+        assert!(constant.exponent().unwrap().abs() < 0.01);
+        assert_eq!(constant.complexity(), Some(Complexity::Constant));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::aoc::*;
-use derive_solution::{parser, solution};
+use derive_solution::{generator, parser, solution};
+use itertools::Itertools;
 
 pub struct Input {
     rotations: Vec<i64>,
@@ -20,6 +21,13 @@ fn parse_input(input: &PuzzleInput) -> Input {
     }
 }
 
+#[generator(day = 1)]
+fn generate_rotations(size: usize, rng: &mut fastrand::Rng) -> String {
+    (0..size)
+        .map(|_| format!("{}{}", rng.choice(['L', 'R']).unwrap(), rng.u32(1..1000)))
+        .join("\n")
+}
+
 #[solution(day = 1, part = 1)]
 fn solve_part_1(input: Input) -> i64 {
     input
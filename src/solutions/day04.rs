@@ -1,5 +1,6 @@
 use crate::aoc::*;
 use crate::utils::grid::{DenseGrid, XY};
+use crate::visualize::{self, Frame, Rgb};
 use derive_solution::{parser, solution};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -8,6 +9,15 @@ pub enum Cell {
     Empty,
 }
 
+impl Cell {
+    fn colour(&self) -> Rgb {
+        match self {
+            Cell::PaperRoll => Rgb::WHITE,
+            Cell::Empty => Rgb::BLACK,
+        }
+    }
+}
+
 #[parser]
 fn parse_input(input: &PuzzleInput) -> DenseGrid<Cell> {
     DenseGrid::from_rows(
@@ -42,6 +52,10 @@ fn solve_part_2(mut grid: DenseGrid<Cell>) -> usize {
             break;
         }
 
+        visualize::emit(|| {
+            Frame::grid(&grid, Cell::colour).points(removable_rolls.clone(), Rgb::RED)
+        });
+
         total_removed += removable_rolls.len();
         removable_rolls.into_iter().for_each(|pos| {
             grid.set_at(pos, Cell::Empty);
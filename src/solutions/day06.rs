@@ -93,8 +93,7 @@ fn parse_input_part_2(input: &PuzzleInput) -> Vec<CephalopodColumn> {
     let last_line = input
         .get_raw()
         .split('\n')
-        .filter(|line| !line.trim().is_empty())
-        .next_back()
+        .rfind(|line| !line.trim().is_empty())
         .unwrap();
 
     let mut widths = last_line
@@ -1,5 +1,6 @@
 use crate::aoc::*;
 use crate::utils::grid::{DenseGrid, XY};
+use crate::visualize::{self, Frame, Rgb};
 use derive_solution::{parser, solution};
 use std::collections::HashMap;
 use std::fmt::Display;
@@ -12,6 +13,17 @@ pub enum Cell {
     Beam,
 }
 
+impl Cell {
+    fn colour(&self) -> Rgb {
+        match self {
+            Cell::Empty => Rgb::BLACK,
+            Cell::Source => Rgb::GREEN,
+            Cell::Splitter => Rgb::GREY,
+            Cell::Beam => Rgb::YELLOW,
+        }
+    }
+}
+
 impl Display for Cell {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -65,6 +77,10 @@ fn solve_part_1(mut grid: DenseGrid<Cell>) -> usize {
                 Cell::Source => unreachable!(),
             }
         }
+
+        visualize::emit(|| {
+            Frame::grid(&grid, Cell::colour).points(pending_beams.clone(), Rgb::RED)
+        });
     }
 
     total_splits
@@ -1,5 +1,5 @@
 use crate::aoc::*;
-use derive_solution::{parser, solution};
+use derive_solution::{generator, parser, solution};
 use itertools::Itertools;
 use log::debug;
 use std::collections::{HashMap, HashSet};
@@ -33,6 +33,14 @@ fn parse_input(input: &PuzzleInput) -> Vec<Point> {
     input.get_lines().filter_map(Point::from_str).collect_vec()
 }
 
+/// Random junction boxes in the same range as the real input
+#[generator(day = 8)]
+fn generate_points(size: usize, rng: &mut fastrand::Rng) -> String {
+    (0..size)
+        .map(|_| (0..3).map(|_| rng.u32(0..100_000)).join(","))
+        .join("\n")
+}
+
 #[solution(day = 8, part = 1)]
 fn solve_part_1(input: Vec<Point>) -> usize {
     let total_connections = if cfg!(test) { 10 } else { 1000 };
@@ -113,7 +121,7 @@ fn connect_until(
                 circuit_members
                     .entry(circuit_id)
                     .or_default()
-                    .extend(vec![*a, *b].into_iter());
+                    .extend([*a, *b]);
                 circuit_id += 1;
             }
             (Some(a), Some(b)) if a == b => {
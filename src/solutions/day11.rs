@@ -1,26 +1,24 @@
 use crate::aoc::*;
 use derive_solution::{parser, solution};
-use smol_str::SmolStr;
 use std::collections::HashMap;
 
-pub struct Network {
-    connections: HashMap<SmolStr, Vec<SmolStr>>,
+pub struct Network<'a> {
+    connections: HashMap<&'a str, Vec<&'a str>>,
 }
 
 #[parser]
-fn parse_input(input: &PuzzleInput) -> Network {
+fn parse_input(input: &PuzzleInput) -> Network<'_> {
     let connections = input
         .get_lines()
         .filter(|line| !line.is_empty())
         .map(|line| {
             let (source, targets) = line.split_once(':').unwrap();
             (
-                SmolStr::new(source),
+                source,
                 targets
                     .split(' ')
                     .map(|target| target.trim())
                     .filter(|target| !target.is_empty())
-                    .map(SmolStr::new)
                     .collect(),
             )
         })
@@ -30,41 +28,36 @@ fn parse_input(input: &PuzzleInput) -> Network {
 }
 
 #[solution(day = 11, part = 1)]
-fn solve_part_1(network: Network) -> i64 {
+fn solve_part_1(network: Network<'_>) -> i64 {
     network.count_paths("you", "out")
 }
 
 #[solution(day = 11, part = 2)]
-fn solve_part_2(network: Network) -> i64 {
+fn solve_part_2(network: Network<'_>) -> i64 {
     network.count_paths_going_through_fft_and_dac(
         State {
-            current: SmolStr::from("svr"),
+            current: "svr",
             passed_through_fft: false,
             passed_through_dac: false,
         },
-        SmolStr::from("out"),
+        "out",
         &mut HashMap::new(),
     )
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-struct State {
-    current: SmolStr,
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+struct State<'a> {
+    current: &'a str,
     passed_through_fft: bool,
     passed_through_dac: bool,
 }
 
-impl Network {
-    fn count_paths(&self, from: &str, to: &str) -> i64 {
-        self.count_paths_inner(SmolStr::from(from), SmolStr::from(to), &mut HashMap::new())
+impl<'a> Network<'a> {
+    fn count_paths(&self, from: &'a str, to: &str) -> i64 {
+        self.count_paths_inner(from, to, &mut HashMap::new())
     }
 
-    fn count_paths_inner(
-        &self,
-        from: SmolStr,
-        to: SmolStr,
-        cache: &mut HashMap<SmolStr, i64>,
-    ) -> i64 {
+    fn count_paths_inner(&self, from: &'a str, to: &str, cache: &mut HashMap<&'a str, i64>) -> i64 {
         if from == to {
             return 1;
         }
@@ -73,9 +66,9 @@ impl Network {
             return *cached_value;
         }
 
-        let paths = self.connections[&from]
+        let paths = self.connections[from]
             .iter()
-            .map(|target| self.count_paths_inner(target.clone(), to.clone(), cache))
+            .map(|target| self.count_paths_inner(target, to, cache))
             .sum();
 
         cache.insert(from, paths);
@@ -85,9 +78,9 @@ impl Network {
 
     fn count_paths_going_through_fft_and_dac(
         &self,
-        mut state: State,
-        to: SmolStr,
-        cache: &mut HashMap<State, i64>,
+        mut state: State<'a>,
+        to: &str,
+        cache: &mut HashMap<State<'a>, i64>,
     ) -> i64 {
         if let Some(cached_value) = cache.get(&state) {
             return *cached_value;
@@ -107,15 +100,15 @@ impl Network {
             state.passed_through_dac = true;
         }
 
-        let paths = self.connections[&state.current]
+        let paths = self.connections[state.current]
             .iter()
             .map(|next| {
                 self.count_paths_going_through_fft_and_dac(
                     State {
-                        current: next.clone(),
+                        current: next,
                         ..state
                     },
-                    to.clone(),
+                    to,
                     cache,
                 )
             })
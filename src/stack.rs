@@ -0,0 +1,47 @@
+use std::hint::black_box;
+use std::mem::MaybeUninit;
+
+/// How much of the stack is painted before measuring. Usage beyond this is reported as this size.
+pub const PAINTED_STACK_SIZE: usize = 32 * 1024 * 1024;
+/// Stack size of the thread the benchmarks run on, leaving room for the painted region and
+/// everything above it
+pub const BENCHMARK_STACK_SIZE: usize = 2 * PAINTED_STACK_SIZE;
+
+const WORDS: usize = PAINTED_STACK_SIZE / size_of::<u64>();
+const PATTERN: u64 = 0x57AC_57AC_57AC_57AC;
+
+/// Fill a region of the stack below the caller with a known pattern, returning its lowest address.
+/// Once this returns, the region is free again, and anything called afterwards overwrites it from
+/// the top down.
+#[inline(never)]
+fn paint() -> usize {
+    let mut region = MaybeUninit::<[u64; WORDS]>::uninit();
+    let words = region.as_mut_ptr() as *mut u64;
+    for i in 0..WORDS {
+        unsafe { words.add(i).write_volatile(PATTERN) };
+    }
+    black_box(&mut region);
+    words as usize
+}
+
+/// The number of bytes of the painted region that were overwritten
+#[inline(never)]
+fn high_water_mark(lowest: usize) -> usize {
+    let words = lowest as *const u64;
+    let untouched = (0..WORDS)
+        .take_while(|&i| unsafe { words.add(i).read_volatile() } == PATTERN)
+        .count();
+    (WORDS - untouched) * size_of::<u64>()
+}
+
+/// Run `f` and measure how much stack it used, by painting the stack beforehand and finding the
+/// deepest point that was overwritten. The result includes a few hundred bytes of overhead from the
+/// measurement itself.
+///
+/// This must run on a thread with at least [`BENCHMARK_STACK_SIZE`] of stack, e.g. one spawned with
+/// [`std::thread::Builder::stack_size`].
+pub fn measure_stack<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let lowest = paint();
+    let result = black_box(f());
+    (result, high_water_mark(lowest))
+}
@@ -0,0 +1,273 @@
+use crate::answers::AnswersFile;
+use crate::aoc::{Day, Part, SolverMap, get_days_iter};
+use crate::solutions::get_unsolved_parts;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+pub trait StarSource {
+    /// Returns every (day, part) that has a star on the account
+    fn get_stars(&self) -> anyhow::Result<HashSet<(Day, Part)>>;
+
+    /// The stars from the last time they were fetched, without going online
+    fn get_cached_stars(&self) -> anyhow::Result<Option<HashSet<(Day, Part)>>>;
+
+    /// Hash of the account's input for a day, if it was downloaded before
+    fn get_cached_input_hash(&self, day: Day) -> Option<String>;
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PartStatus {
+    /// The answer was accepted
+    Star,
+    /// There's a solver, but its answer hasn't been accepted
+    Solved,
+    /// The solver is flagged with `#[solution(unsolved)]`
+    Unsolved,
+    Missing,
+}
+
+impl Display for PartStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartStatus::Star => write!(f, "star"),
+            PartStatus::Solved => write!(f, "no star"),
+            PartStatus::Unsolved => write!(f, "unsolved"),
+            PartStatus::Missing => write!(f, "-"),
+        }
+    }
+}
+
+pub struct Stars {
+    /// `None` if there's nothing to tell which parts have a star
+    earned: Option<HashSet<(Day, Part)>>,
+    unsolved: HashSet<(Day, Part)>,
+}
+
+impl Stars {
+    /// Uses the accepted answers in `answers.toml` if it exists, otherwise the account's calendar.
+    /// Only answers for this account's inputs count, so a day whose input was never downloaded has
+    /// no star from `answers.toml`.
+    pub fn load(source: &impl StarSource) -> Self {
+        Self::from_answers_or(source, || {
+            source.get_stars().map(Some).unwrap_or_else(|e| {
+                log::warn!("failed to get stars: {e}");
+                None
+            })
+        })
+    }
+
+    /// Like [`Stars::load`], but only with what's on disk, falling back to the calendar from the
+    /// last time it was downloaded. The stars are unknown if there's neither.
+    pub fn load_offline(source: &impl StarSource) -> Self {
+        Self::from_answers_or(source, || {
+            source.get_cached_stars().unwrap_or_else(|e| {
+                log::warn!("failed to read the cached stars: {e}");
+                None
+            })
+        })
+    }
+
+    fn from_answers_or(
+        source: &impl StarSource,
+        fallback: impl FnOnce() -> Option<HashSet<(Day, Part)>>,
+    ) -> Self {
+        let earned = match AnswersFile::load() {
+            Ok(Some(answers)) => Some(accepted_answers(&answers, |day| {
+                source.get_cached_input_hash(day)
+            })),
+            Ok(None) => fallback(),
+            Err(e) => {
+                log::warn!("failed to load answers file: {e}");
+                None
+            }
+        };
+
+        Stars {
+            earned,
+            unsolved: get_unsolved_parts(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn new(earned: HashSet<(Day, Part)>, unsolved: HashSet<(Day, Part)>) -> Self {
+        Stars {
+            earned: Some(earned),
+            unsolved,
+        }
+    }
+
+    /// `None` if the stars are unknown
+    pub fn count(&self) -> Option<usize> {
+        let earned = self.earned.as_ref()?;
+        Some(
+            get_days_iter()
+                .flat_map(|day| [(day, 1), (day, 2)])
+                .filter(|key| earned.contains(key))
+                .count(),
+        )
+    }
+
+    pub fn total() -> usize {
+        get_days_iter().count() * 2
+    }
+
+    pub fn has_star(&self, day: Day, part: Part) -> bool {
+        self.earned
+            .as_ref()
+            .is_some_and(|earned| earned.contains(&(day, part)))
+    }
+
+    /// Known to have no star, which is false when the stars are unknown
+    pub fn lacks_star(&self, day: Day, part: Part) -> bool {
+        self.earned
+            .as_ref()
+            .is_some_and(|earned| !earned.contains(&(day, part)))
+    }
+
+    pub fn is_unsolved(&self, day: Day, part: Part) -> bool {
+        self.unsolved.contains(&(day, part))
+    }
+
+    pub fn status(&self, day: Day, part: Part, solvers: &SolverMap) -> PartStatus {
+        if self.has_star(day, part) {
+            PartStatus::Star
+        } else if self.is_unsolved(day, part) {
+            PartStatus::Unsolved
+        } else if solvers.contains_key(&(day, part)) {
+            PartStatus::Solved
+        } else {
+            PartStatus::Missing
+        }
+    }
+
+    pub fn star_road(&self) -> String {
+        let total_stars = Self::total();
+        let Some(current_stars) = self.count() else {
+            return format!("`|{}| ?/{total_stars} stars`", "-".repeat(total_stars));
+        };
+        format!(
+            "`|{}{}| {current_stars}/{total_stars} stars`",
+            "#".repeat(current_stars),
+            "-".repeat(total_stars - current_stars)
+        )
+    }
+}
+
+/// The parts with an answer that isn't unverified, for the input `input_hash` returns for its day
+fn accepted_answers(
+    answers: &AnswersFile,
+    input_hash: impl Fn(Day) -> Option<String>,
+) -> HashSet<(Day, Part)> {
+    let input_hashes: HashMap<Day, String> = get_days_iter()
+        .filter_map(|day| Some((day, input_hash(day)?)))
+        .collect();
+    answers
+        .answers
+        .iter()
+        .filter(|answer| !answer.unverified)
+        .filter(|answer| {
+            answer
+                .input
+                .as_ref()
+                .is_some_and(|input| input_hashes.get(&answer.day) == Some(input))
+        })
+        .map(|answer| (answer.day, answer.part))
+        .collect()
+}
+
+lazy_static! {
+    static ref CALENDAR_DAY_REGEX: Regex =
+        Regex::new(r#"aria-label="Day (?<day>\d+), (?<stars>one star|two stars)""#).unwrap();
+}
+
+/// Reads the stars from the `aria-label`s on the calendar page, e.g. `aria-label="Day 1, two stars"`
+pub fn parse_calendar_stars(html: &str) -> HashSet<(Day, Part)> {
+    CALENDAR_DAY_REGEX
+        .captures_iter(html)
+        .flat_map(|captures| {
+            let day: Day = captures["day"].parse().unwrap();
+            match &captures["stars"] {
+                "two stars" => vec![(day, 1), (day, 2)],
+                _ => vec![(day, 1)],
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::KnownAnswer;
+
+    #[test]
+    fn test_parse_calendar_stars() {
+        let html = r#"
+<a aria-label="Day 1, two stars" href="/2025/day/1" class="calendar-day1 calendar-verycomplete">
+<a aria-label="Day 2, one star" href="/2025/day/2" class="calendar-day2 calendar-complete">
+<a aria-label="Day 3" href="/2025/day/3" class="calendar-day3">
+"#;
+        assert_eq!(
+            parse_calendar_stars(html),
+            HashSet::from([(1, 1), (1, 2), (2, 1)])
+        );
+    }
+
+    #[test]
+    fn test_part_status() {
+        let mut solvers: SolverMap = HashMap::new();
+        solvers.insert((1, 1), Box::new(|_| Some("1".to_string())));
+        solvers.insert((1, 2), Box::new(|_| Some("2".to_string())));
+        solvers.insert((2, 1), Box::new(|_| None));
+
+        let stars = Stars::new(HashSet::from([(1, 1)]), HashSet::from([(2, 1)]));
+
+        assert_eq!(stars.status(1, 1, &solvers), PartStatus::Star);
+        assert_eq!(stars.status(1, 2, &solvers), PartStatus::Solved);
+        assert_eq!(stars.status(2, 1, &solvers), PartStatus::Unsolved);
+        assert_eq!(stars.status(2, 2, &solvers), PartStatus::Missing);
+        assert_eq!(stars.count(), Some(1));
+    }
+
+    #[test]
+    fn test_unknown_stars() {
+        let stars = Stars {
+            earned: None,
+            unsolved: HashSet::new(),
+        };
+
+        assert_eq!(stars.count(), None);
+        assert!(!stars.has_star(1, 1));
+        assert!(!stars.lacks_star(1, 1));
+        assert!(stars.star_road().ends_with("| ?/24 stars`"));
+    }
+
+    #[test]
+    fn test_only_answers_for_this_account_count() {
+        let answer = |day, part, input: Option<&str>, unverified| KnownAnswer {
+            day,
+            part,
+            input: input.map(str::to_string),
+            answer: "1".to_string(),
+            unverified,
+        };
+        let answers = AnswersFile {
+            answers: vec![
+                answer(1, 1, Some("mine"), false),
+                answer(1, 2, Some("mine"), true),
+                answer(2, 1, Some("theirs"), false),
+                answer(3, 1, None, false),
+                answer(4, 1, Some("mine"), false),
+                answer(4, 2, None, false),
+            ],
+        };
+        // day 4's input was never downloaded
+        let input_hash = |day| (day != 4).then(|| "mine".to_string());
+
+        assert_eq!(
+            accepted_answers(&answers, input_hash),
+            HashSet::from([(1, 1)])
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use crate::aoc::{Day, Part, PuzzleInput, PuzzleSource, SolverMap};
+use crate::bench::{BenchmarkPolicy, BenchmarkResults, format_duration};
+use crate::benchmark_solver;
+use crate::readme::format_table;
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::thread::available_parallelism;
+
+/// A rayon pool size given on the command line
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ThreadCount {
+    Count(usize),
+    /// One thread per available core
+    Max,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid thread count {0}, expected a positive number or max")]
+pub struct InvalidThreadCount(String);
+
+impl FromStr for ThreadCount {
+    type Err = InvalidThreadCount;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "max" => Ok(Self::Max),
+            _ => match s.parse() {
+                Ok(0) | Err(_) => Err(InvalidThreadCount(s.to_string())),
+                Ok(count) => Ok(Self::Count(count)),
+            },
+        }
+    }
+}
+
+impl Display for ThreadCount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Count(count) => write!(f, "{count}"),
+            Self::Max => write!(f, "max ({})", self.resolve()),
+        }
+    }
+}
+
+impl ThreadCount {
+    pub fn resolve(self) -> usize {
+        match self {
+            Self::Count(count) => count,
+            Self::Max => available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    pub fn build_pool(self) -> anyhow::Result<rayon::ThreadPool> {
+        Ok(rayon::ThreadPoolBuilder::new()
+            .num_threads(self.resolve())
+            .thread_name(|i| format!("rayon-{i}"))
+            .build()?)
+    }
+}
+
+/// The benchmarks of one part with every pool size
+#[derive(Debug, Clone)]
+pub struct ThreadScaling {
+    pub day: Day,
+    pub part: Part,
+    pub points: Vec<(ThreadCount, BenchmarkResults)>,
+}
+
+impl ThreadScaling {
+    /// How many times faster each pool size is than the first one, and the speedup divided by the
+    /// extra threads. An efficiency of 1 means the part scales perfectly.
+    pub fn speedups(&self) -> Vec<(f64, f64)> {
+        let Some((base_threads, base)) = self.points.first() else {
+            return Vec::new();
+        };
+        self.points
+            .iter()
+            .map(|(threads, result)| {
+                let speedup = base.average_duration.as_secs_f64()
+                    / result.average_duration.as_secs_f64().max(f64::MIN_POSITIVE);
+                let extra_threads = threads.resolve() as f64 / base_threads.resolve() as f64;
+                (speedup, speedup / extra_threads)
+            })
+            .collect()
+    }
+}
+
+/// Benchmark every solved part of the given days inside a rayon pool of each size
+pub fn run_thread_scaling(
+    solver_map: &SolverMap,
+    puzzle_source: &impl PuzzleSource,
+    days: impl Iterator<Item = Day>,
+    thread_counts: &[ThreadCount],
+    policy: &BenchmarkPolicy,
+) -> anyhow::Result<Vec<ThreadScaling>> {
+    let pools = thread_counts
+        .iter()
+        .map(|threads| Ok((*threads, threads.build_pool()?)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let mut results = Vec::new();
+
+    for day in days {
+        if !(1..=2).any(|part| solver_map.contains_key(&(day, part))) {
+            continue;
+        }
+        let input = puzzle_source.get_input(day)?;
+
+        for part in 1..=2 as Part {
+            let Some(solver) = solver_map.get(&(day, part)) else {
+                continue;
+            };
+            let mut scaling = ThreadScaling {
+                day,
+                part,
+                points: Vec::new(),
+            };
+            for (threads, pool) in &pools {
+                // the benchmark runs on its own thread, so every run has to enter the pool
+                let in_pool = |input: &PuzzleInput| pool.install(|| solver(input));
+                match benchmark_solver(day, &in_pool, &input, policy) {
+                    Ok(result) => {
+                        log::info!(
+                            "day {day} part {part} with {threads} threads: {}",
+                            format_duration(result.average_duration)
+                        );
+                        scaling.points.push((*threads, result));
+                    }
+                    Err(e) => log::debug!("day {day} part {part}: {e}"),
+                }
+            }
+            if !scaling.points.is_empty() {
+                results.push(scaling);
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// A row per part and pool size
+pub fn format_thread_scaling(results: &[ThreadScaling]) -> String {
+    let rows = results
+        .iter()
+        .flat_map(|scaling| {
+            scaling.points.iter().zip(scaling.speedups()).map(
+                |((threads, result), (speedup, efficiency))| {
+                    [
+                        scaling.day.to_string(),
+                        scaling.part.to_string(),
+                        threads.to_string(),
+                        format_duration(result.average_duration),
+                        format!("{speedup:.2}x"),
+                        format!("{:.0}%", efficiency * 100.0),
+                    ]
+                },
+            )
+        })
+        .collect_vec();
+
+    format_table(
+        &["Day", "Part", "Threads", "Time", "Speedup", "Efficiency"],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_thread_count() {
+        assert_eq!("4".parse::<ThreadCount>().unwrap(), ThreadCount::Count(4));
+        assert_eq!("max".parse::<ThreadCount>().unwrap(), ThreadCount::Max);
+        assert!("0".parse::<ThreadCount>().is_err());
+        assert!("all".parse::<ThreadCount>().is_err());
+        assert!(ThreadCount::Max.resolve() >= 1);
+    }
+
+    #[test]
+    fn test_speedups() {
+        let result = |millis| BenchmarkResults::fixture(Duration::from_millis(millis));
+        let scaling = ThreadScaling {
+            day: 9,
+            part: 2,
+            points: vec![
+                (ThreadCount::Count(1), result(100)),
+                (ThreadCount::Count(2), result(50)),
+                (ThreadCount::Count(4), result(40)),
+            ],
+        };
+
+        assert_eq!(
+            scaling.speedups(),
+            vec![(1.0, 1.0), (2.0, 1.0), (2.5, 0.625)]
+        );
+    }
+
+    #[test]
+    fn test_solver_runs_in_pool() {
+        let pool = ThreadCount::Count(3).build_pool().unwrap();
+        let threads = pool.install(rayon::current_num_threads);
+        assert_eq!(threads, 3);
+    }
+}
@@ -1,6 +1,9 @@
+// helpers for the solutions, which don't all use every one of them
+#[allow(dead_code)]
 pub mod grid;
 
 use itertools::Itertools;
+use std::fmt::Write;
 
 pub fn get_cpu_name() -> String {
     let s = sysinfo::System::new_with_specifics(sysinfo::RefreshKind::everything());
@@ -13,3 +16,46 @@ pub fn get_cpu_name() -> String {
         .join(", ")
         .to_string()
 }
+
+pub fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut output, b| {
+        let _ = write!(output, "{b:02x}");
+        output
+    })
+}
+
+/// Formats seconds since the Unix epoch as `YYYY-MM-DD HH:MM` (UTC)
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds_of_day = timestamp % 86400;
+
+    // civil_from_days, from http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        seconds_of_day / 3600,
+        (seconds_of_day % 3600) / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1764547200), "2025-12-01 00:00");
+        assert_eq!(format_timestamp(1709210096), "2024-02-29 12:34");
+    }
+}
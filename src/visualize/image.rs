@@ -0,0 +1,83 @@
+use crate::visualize::{Frame, Rgb, Visualizer};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+}
+
+impl ImageFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+/// Writes every frame as a numbered image (`frame_00000.png`, ...) in a directory
+pub struct ImageSequenceVisualizer {
+    directory: PathBuf,
+    format: ImageFormat,
+    frame_index: usize,
+}
+
+impl ImageSequenceVisualizer {
+    pub fn new(directory: PathBuf, format: ImageFormat) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&directory)?;
+        Ok(ImageSequenceVisualizer {
+            directory,
+            format,
+            frame_index: 0,
+        })
+    }
+}
+
+impl Visualizer for ImageSequenceVisualizer {
+    fn show(&mut self, frame: &Frame) -> std::io::Result<()> {
+        let canvas = frame.render();
+        let (width, height) = (canvas.width() as u32, canvas.height() as u32);
+        let data = canvas
+            .items()
+            .flat_map(|(_, &Rgb(r, g, b))| [r, g, b])
+            .collect::<Vec<_>>();
+
+        let path = self
+            .directory
+            .join(format!("frame_{:05}", self.frame_index))
+            .with_extension(self.format.extension());
+        let mut file = BufWriter::new(File::create(path)?);
+
+        match self.format {
+            ImageFormat::Ppm => {
+                write!(file, "P6\n{width} {height}\n255\n")?;
+                file.write_all(&data)?;
+            }
+            ImageFormat::Png => {
+                let mut encoder = png::Encoder::new(&mut file, width, height);
+                encoder.set_color(png::ColorType::Rgb);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder
+                    .write_header()
+                    .and_then(|mut writer| writer.write_image_data(&data))
+                    .map_err(std::io::Error::other)?;
+            }
+        }
+
+        file.flush()?;
+        self.frame_index += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        log::info!(
+            "wrote {} frames to {}",
+            self.frame_index,
+            self.directory.display()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//! Optional visualisation hook for solvers.
+//!
+//! Solvers call [`emit`] with a closure that builds a [`Frame`]. Unless a [`Visualizer`] has been
+//! installed (via `solve --visualize`), the closure is never called, so the hook costs a single
+//! atomic load when it's turned off.
+
+pub mod image;
+pub mod terminal;
+
+use crate::utils::grid::{DenseGrid, XY};
+use std::sync::{Mutex, OnceLock};
+
+/// Frames larger than this (in either direction) are scaled down when rendered
+const MAX_CANVAS_SIZE: i64 = 512;
+
+static VISUALIZER: OnceLock<Mutex<Box<dyn Visualizer>>> = OnceLock::new();
+
+pub trait Visualizer: Send {
+    fn show(&mut self, frame: &Frame) -> std::io::Result<()>;
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Install the visualizer that receives every emitted frame. Only the first call has an effect.
+pub fn install(visualizer: Box<dyn Visualizer>) {
+    if VISUALIZER.set(Mutex::new(visualizer)).is_err() {
+        log::warn!("a visualizer is already installed");
+    }
+}
+
+pub fn is_enabled() -> bool {
+    VISUALIZER.get().is_some()
+}
+
+/// Send a frame to the installed visualizer, if any. The frame is only built when one is installed.
+#[inline(always)]
+pub fn emit(build_frame: impl FnOnce() -> Frame) {
+    if let Some(visualizer) = VISUALIZER.get() {
+        let frame = build_frame();
+        if let Err(e) = visualizer.lock().unwrap().show(&frame) {
+            log::warn!("failed to show frame: {e}");
+        }
+    }
+}
+
+/// Flush any pending output of the installed visualizer
+pub fn finish() {
+    if let Some(visualizer) = VISUALIZER.get()
+        && let Err(e) = visualizer.lock().unwrap().finish()
+    {
+        log::warn!("failed to finish visualization: {e}");
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GREY: Rgb = Rgb(96, 96, 96);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(80, 200, 80);
+    pub const BLUE: Rgb = Rgb(38, 139, 210);
+    pub const YELLOW: Rgb = Rgb(240, 200, 40);
+}
+
+#[derive(Debug, Clone)]
+enum Shape {
+    Points(Vec<XY>, Rgb),
+    Path(Vec<XY>, Rgb),
+}
+
+/// A single frame: an optional coloured grid with point sets and paths drawn on top of it
+#[derive(Debug, Clone, Default)]
+pub struct Frame {
+    grid: Option<DenseGrid<Rgb>>,
+    shapes: Vec<Shape>,
+}
+
+impl Frame {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn grid<T: Clone>(grid: &DenseGrid<T>, colour: impl Fn(&T) -> Rgb) -> Self {
+        Frame {
+            grid: Some(DenseGrid::from_iter(
+                grid.width() as usize,
+                grid.items().map(|(_, cell)| colour(cell)),
+            )),
+            shapes: vec![],
+        }
+    }
+
+    pub fn points(mut self, points: impl IntoIterator<Item = XY>, colour: Rgb) -> Self {
+        self.shapes
+            .push(Shape::Points(points.into_iter().collect(), colour));
+        self
+    }
+
+    pub fn path(mut self, points: impl IntoIterator<Item = XY>, colour: Rgb) -> Self {
+        self.shapes
+            .push(Shape::Path(points.into_iter().collect(), colour));
+        self
+    }
+
+    fn bounds(&self) -> Option<(XY, XY)> {
+        let grid_corners = self.grid.iter().flat_map(|grid| {
+            [
+                XY { x: 0, y: 0 },
+                XY {
+                    x: grid.width() - 1,
+                    y: grid.height() - 1,
+                },
+            ]
+        });
+        let shape_points = self.shapes.iter().flat_map(|shape| match shape {
+            Shape::Points(points, _) | Shape::Path(points, _) => points.iter().copied(),
+        });
+
+        grid_corners.chain(shape_points).fold(None, |bounds, p| {
+            Some(match bounds {
+                None => (p, p),
+                Some((min, max)) => (
+                    XY {
+                        x: min.x.min(p.x),
+                        y: min.y.min(p.y),
+                    },
+                    XY {
+                        x: max.x.max(p.x),
+                        y: max.y.max(p.y),
+                    },
+                ),
+            })
+        })
+    }
+
+    /// Rasterise the frame, scaling it down if it's larger than [`MAX_CANVAS_SIZE`]
+    pub fn render(&self) -> DenseGrid<Rgb> {
+        let Some((min, max)) = self.bounds() else {
+            return DenseGrid::from_rows(vec![vec![Rgb::BLACK]]);
+        };
+
+        let size = max - min + XY { x: 1, y: 1 };
+        let scale = (size.x.max(size.y) + MAX_CANVAS_SIZE - 1) / MAX_CANVAS_SIZE;
+        let to_canvas = |p: XY| XY {
+            x: (p.x - min.x) / scale,
+            y: (p.y - min.y) / scale,
+        };
+
+        let mut canvas = DenseGrid::from_iter(
+            ((size.x + scale - 1) / scale) as usize,
+            std::iter::repeat_n(
+                Rgb::BLACK,
+                (((size.x + scale - 1) / scale) * ((size.y + scale - 1) / scale)) as usize,
+            ),
+        );
+
+        if let Some(grid) = &self.grid {
+            grid.items().for_each(|(pos, colour)| {
+                canvas.set_at(to_canvas(pos), *colour);
+            });
+        }
+
+        for shape in &self.shapes {
+            match shape {
+                Shape::Points(points, colour) => points.iter().for_each(|p| {
+                    canvas.set_at(to_canvas(*p), *colour);
+                }),
+                Shape::Path(points, colour) => {
+                    for (a, b) in points.iter().zip(points.iter().skip(1)) {
+                        draw_line(&mut canvas, to_canvas(*a), to_canvas(*b), *colour);
+                    }
+                    if let [single] = points.as_slice() {
+                        canvas.set_at(to_canvas(*single), *colour);
+                    }
+                }
+            }
+        }
+
+        canvas
+    }
+}
+
+/// Bresenham's line algorithm
+fn draw_line(canvas: &mut DenseGrid<Rgb>, from: XY, to: XY, colour: Rgb) {
+    let dx = (to.x - from.x).abs();
+    let dy = -(to.y - from.y).abs();
+    let step = XY {
+        x: (to.x - from.x).signum(),
+        y: (to.y - from.y).signum(),
+    };
+    let mut error = dx + dy;
+    let mut pos = from;
+
+    loop {
+        canvas.set_at(pos, colour);
+        if pos == to {
+            break;
+        }
+        let e2 = 2 * error;
+        if e2 >= dy {
+            error += dy;
+            pos.x += step.x;
+        }
+        if e2 <= dx {
+            error += dx;
+            pos.y += step.y;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_grid_with_points() {
+        let grid = DenseGrid::from_rows(vec![vec![false, true], vec![true, false]]);
+        let canvas = Frame::grid(&grid, |&cell| if cell { Rgb::WHITE } else { Rgb::BLACK })
+            .points([XY { x: 0, y: 0 }], Rgb::RED)
+            .render();
+
+        assert_eq!(canvas.width(), 2);
+        assert_eq!(canvas.height(), 2);
+        assert_eq!(canvas.at(XY { x: 0, y: 0 }), Some(&Rgb::RED));
+        assert_eq!(canvas.at(XY { x: 1, y: 0 }), Some(&Rgb::WHITE));
+        assert_eq!(canvas.at(XY { x: 1, y: 1 }), Some(&Rgb::BLACK));
+    }
+
+    #[test]
+    fn test_render_path() {
+        let canvas = Frame::new()
+            .path(
+                [XY { x: 0, y: 0 }, XY { x: 3, y: 0 }, XY { x: 3, y: 2 }],
+                Rgb::BLUE,
+            )
+            .render();
+
+        assert_eq!(canvas.width(), 4);
+        assert_eq!(canvas.height(), 3);
+        assert_eq!(canvas.find(&Rgb::BLUE).count(), 6);
+    }
+
+    #[test]
+    fn test_render_scales_large_frames() {
+        let canvas = Frame::new()
+            .points(
+                [
+                    XY { x: 0, y: 0 },
+                    XY {
+                        x: 99_999,
+                        y: 49_999,
+                    },
+                ],
+                Rgb::GREEN,
+            )
+            .render();
+
+        assert!(canvas.width() <= MAX_CANVAS_SIZE);
+        assert!(canvas.height() <= MAX_CANVAS_SIZE);
+        assert_eq!(canvas.find(&Rgb::GREEN).count(), 2);
+    }
+}
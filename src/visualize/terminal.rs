@@ -0,0 +1,42 @@
+use crate::utils::grid::XY;
+use crate::visualize::{Frame, Rgb, Visualizer};
+use std::io::Write;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// Animates frames in the terminal using 24-bit colour half blocks, two grid rows per line. Frames
+/// go to stderr, so the answers on stdout stay clean for `--format json`.
+pub struct TerminalVisualizer {
+    frame_delay: Duration,
+}
+
+impl TerminalVisualizer {
+    pub fn new(frame_delay: Duration) -> Self {
+        TerminalVisualizer { frame_delay }
+    }
+}
+
+impl Visualizer for TerminalVisualizer {
+    fn show(&mut self, frame: &Frame) -> std::io::Result<()> {
+        let canvas = frame.render();
+        let mut out = String::from("\x1b[H\x1b[2J");
+
+        for y in (0..canvas.height()).step_by(2) {
+            for x in 0..canvas.width() {
+                let Rgb(r1, g1, b1) = *canvas.at(XY { x, y }).unwrap();
+                let Rgb(r2, g2, b2) = *canvas.at(XY { x, y: y + 1 }).unwrap_or(&Rgb::BLACK);
+                out.push_str(&format!(
+                    "\x1b[38;2;{r1};{g1};{b1}m\x1b[48;2;{r2};{g2};{b2}m▀"
+                ));
+            }
+            out.push_str("\x1b[0m\n");
+        }
+
+        let mut stderr = std::io::stderr().lock();
+        stderr.write_all(out.as_bytes())?;
+        stderr.flush()?;
+
+        sleep(self.frame_delay);
+        Ok(())
+    }
+}